@@ -1,5 +1,6 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::cmp;
+use std::collections::{HashMap, HashSet};
 use std::mem;
 use std::ops::Deref;
 use std::ops::Range;
@@ -9,19 +10,22 @@ use std::time::Instant;
 use std::fmt;
 
 use compact_arena::{SmallArena, Idx32, mk_arena};
-use log::{debug, error};
+use log::debug;
+use ordered_float::OrderedFloat;
 use sdset::{Set, SetBuf, exponential_search, SetOperation, Counter, duo::OpBuilder};
 use slice_group_by::{GroupBy, GroupByMut};
 
+use meilisearch_schema::{FieldId, Schema};
 use meilisearch_types::DocIndex;
 
 use crate::criterion::{Criteria, Context, ContextMut};
 use crate::distinct_map::{BufferedDistinctMap, DistinctMap};
 use crate::raw_document::RawDocument;
+use crate::settings::{RankingRule, TypoTolerance};
 use crate::{database::MainT, reordered_attrs::ReorderedAttrs};
-use crate::{store, Document, DocumentId, MResult, Index, RankedMap, MainReader, Error};
-use crate::query_tree::{create_query_tree, traverse_query_tree};
-use crate::query_tree::{Operation, QueryResult, QueryKind, QueryId, PostingsKey};
+use crate::{store, Cursor, Document, DocumentId, GeoPoint, MResult, Index, Number, RankedMap, MainReader, Error};
+use crate::query_tree::{create_query_tree, traverse_query_tree, excluded_documents};
+use crate::query_tree::{Operation, QueryResult, QueryKind, QueryId, PostingsKey, MatchingStrategy};
 use crate::query_tree::Context as QTContext;
 
 #[derive(Debug, Default)]
@@ -29,10 +33,43 @@ pub struct SortResult {
     pub documents: Vec<Document>,
     pub nb_hits: usize,
     pub exhaustive_nb_hit: bool,
+    /// The search ran out of time, the documents are the best found so far.
+    pub partial: bool,
+    /// Points to the last document when the page is full, the next page starts after it.
+    pub cursor: Option<Cursor>,
     pub facets: Option<HashMap<String, HashMap<String, usize>>>,
     pub exhaustive_facets_count: Option<bool>,
 }
 
+/// How the number of documents matching a query is computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NbHits {
+    /// The filter and the distinct rule are only applied to the documents needed to fill
+    /// the requested range, the documents they would reject further are still counted.
+    Estimated,
+    /// Every candidate goes through the filter and the distinct rule.
+    Exhaustive,
+    /// Every candidate goes through the filter and the distinct rule, until the given
+    /// number of documents is reached.
+    Capped(usize),
+}
+
+impl Default for NbHits {
+    fn default() -> NbHits {
+        NbHits::Estimated
+    }
+}
+
+impl NbHits {
+    /// The number of documents at which the count stops, if any.
+    pub fn cap(self) -> Option<usize> {
+        match self {
+            NbHits::Capped(cap) => Some(cap),
+            _ => None,
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn bucket_sort<'c, FI>(
     reader: &heed::RoTxn<MainT>,
@@ -43,6 +80,12 @@ pub fn bucket_sort<'c, FI>(
     filter: Option<FI>,
     criteria: Criteria<'c>,
     searchable_attrs: Option<ReorderedAttrs>,
+    ranking_infos: bool,
+    deadline: Option<Instant>,
+    cursor: Option<&Cursor>,
+    nb_hits: NbHits,
+    matching_strategy: MatchingStrategy,
+    exact_words: bool,
     index: &Index,
 ) -> MResult<SortResult>
 where
@@ -64,6 +107,12 @@ where
             distinct_size,
             criteria,
             searchable_attrs,
+            ranking_infos,
+            deadline,
+            cursor,
+            nb_hits,
+            matching_strategy,
+            exact_words,
             index,
         );
     }
@@ -73,15 +122,23 @@ where
     let words_set = index.main.words_fst(reader)?;
     let stop_words = index.main.stop_words_fst(reader)?;
 
+    let schema = index.main.schema(reader)?.ok_or(Error::SchemaMissing)?;
+    let typo_tolerance = index.main.typo_tolerance(reader)?.unwrap_or_default();
+    let typo_disabled_attributes = typo_disabled_attributes(&schema, &typo_tolerance);
+
     let context = QTContext {
         words_set,
         stop_words,
         synonyms: index.synonyms,
         postings_lists: index.postings_lists,
         prefix_postings_lists: index.prefix_postings_lists_cache,
+        typo_tolerance,
+        typo_disabled_attributes,
+        matching_strategy,
+        exact_words,
     };
 
-    let (operation, mapping) = create_query_tree(reader, &context, query)?;
+    let (operation, mapping, excluded) = create_query_tree(reader, &context, query)?;
     debug!("operation:\n{:?}", operation);
     debug!("mapping:\n{:?}", mapping);
 
@@ -100,6 +157,22 @@ where
     debug!("found {} documents", docids.len());
     debug!("number of postings {:?}", queries.len());
 
+    if let Some(reordered_attrs) = &searchable_attrs {
+        let matching_docids = searchable_attributes_docids(&queries, reordered_attrs);
+        let intersection = OpBuilder::new(docids.as_ref(), matching_docids.as_set())
+            .intersection()
+            .into_set_buf();
+        docids = Cow::Owned(intersection);
+    }
+
+    if !excluded.is_empty() {
+        let excluded_docids = excluded_documents(reader, &context, &excluded)?;
+        let difference = sdset::duo::OpBuilder::new(docids.as_ref(), excluded_docids.as_set())
+            .difference()
+            .into_set_buf();
+        docids = Cow::Owned(difference);
+    }
+
     if let Some(facets_docids) = facets_docids {
         let intersection = sdset::duo::OpBuilder::new(docids.as_ref(), facets_docids.as_set())
             .intersection()
@@ -131,16 +204,31 @@ where
         before_raw_documents_building.elapsed(),
     );
 
+    if let Some(cursor) = cursor {
+        documents_after_cursor(reader, &criteria, &mut raw_documents, &mut arena, &mapping, index, cursor)?;
+    }
+
     let before_criterion_loop = Instant::now();
     let proximity_count = AtomicUsize::new(0);
 
     let mut groups = vec![raw_documents.as_mut_slice()];
 
+    let timed_out = || deadline.map_or(false, |deadline| Instant::now() >= deadline);
+
     'criteria: for criterion in criteria.as_ref() {
+
         let tmp_groups = mem::replace(&mut groups, Vec::new());
         let mut documents_seen = 0;
 
         for mut group in tmp_groups {
+            // once the search ran out of time the groups are left as they are sorted,
+            // the documents are the best found so far
+            if timed_out() {
+                debug!("search timed out during the {:?} criterion", criterion.name());
+                result.partial = true;
+                break 'criteria;
+            }
+
             let before_criterion_preparation = Instant::now();
 
             let ctx = ContextMut {
@@ -180,15 +268,37 @@ where
     debug!("criterion loop took {:.02?}", before_criterion_loop.elapsed());
     debug!("proximity evaluation called {} times", proximity_count.load(Ordering::Relaxed));
 
-    let schema = index.main.schema(reader)?.ok_or(Error::SchemaMissing)?;
+    let infos = if ranking_infos {
+        let start = cmp::min(range.start, raw_documents.len());
+        let end = cmp::min(range.end, raw_documents.len());
+        let documents = &mut raw_documents[start..end];
+        documents_ranking_infos(reader, &criteria, documents, &mut arena, &mapping, index)?
+    } else {
+        Vec::new()
+    };
+
+    // the next page starts after the last document of a full page, the documents
+    // of a search that timed out are not all sorted and can't be paginated
+    if !result.partial && range.end <= raw_documents.len() && !range.is_empty() {
+        let last = &mut raw_documents[range.end - 1];
+        result.cursor = Some(document_cursor(reader, &criteria, last, &mut arena, &mapping, index)?);
+    }
+
     let iter = raw_documents.into_iter().skip(range.start).take(range.len());
     let iter = iter.map(|rd| Document::from_raw(rd, &queries_kinds, &arena, searchable_attrs.as_ref(), &schema));
-    let documents = iter.collect();
+    let mut documents: Vec<_> = iter.collect();
+
+    for (document, infos) in documents.iter_mut().zip(infos) {
+        document.ranking_infos = infos;
+    }
 
     debug!("bucket sort took {:.02?}", before_bucket_sort.elapsed());
 
     result.documents = documents;
     result.nb_hits = docids.len();
+    // without filter nor distinct rule all the candidates are counted anyway,
+    // even when the search ran out of time
+    result.exhaustive_nb_hit = true;
 
     Ok(result)
 }
@@ -205,6 +315,12 @@ pub fn bucket_sort_with_distinct<'c, FI, FD>(
     distinct_size: usize,
     criteria: Criteria<'c>,
     searchable_attrs: Option<ReorderedAttrs>,
+    ranking_infos: bool,
+    deadline: Option<Instant>,
+    cursor: Option<&Cursor>,
+    nb_hits: NbHits,
+    matching_strategy: MatchingStrategy,
+    exact_words: bool,
     index: &Index,
 ) -> MResult<SortResult>
 where
@@ -217,15 +333,23 @@ where
     let words_set = index.main.words_fst(reader)?;
     let stop_words = index.main.stop_words_fst(reader)?;
 
+    let schema = index.main.schema(reader)?.ok_or(Error::SchemaMissing)?;
+    let typo_tolerance = index.main.typo_tolerance(reader)?.unwrap_or_default();
+    let typo_disabled_attributes = typo_disabled_attributes(&schema, &typo_tolerance);
+
     let context = QTContext {
         words_set,
         stop_words,
         synonyms: index.synonyms,
         postings_lists: index.postings_lists,
         prefix_postings_lists: index.prefix_postings_lists_cache,
+        typo_tolerance,
+        typo_disabled_attributes,
+        matching_strategy,
+        exact_words,
     };
 
-    let (operation, mapping) = create_query_tree(reader, &context, query)?;
+    let (operation, mapping, excluded) = create_query_tree(reader, &context, query)?;
     debug!("operation:\n{:?}", operation);
     debug!("mapping:\n{:?}", mapping);
 
@@ -244,6 +368,22 @@ where
     debug!("found {} documents", docids.len());
     debug!("number of postings {:?}", queries.len());
 
+    if let Some(reordered_attrs) = &searchable_attrs {
+        let matching_docids = searchable_attributes_docids(&queries, reordered_attrs);
+        let intersection = OpBuilder::new(docids.as_ref(), matching_docids.as_set())
+            .intersection()
+            .into_set_buf();
+        docids = Cow::Owned(intersection);
+    }
+
+    if !excluded.is_empty() {
+        let excluded_docids = excluded_documents(reader, &context, &excluded)?;
+        let difference = sdset::duo::OpBuilder::new(docids.as_ref(), excluded_docids.as_set())
+            .difference()
+            .into_set_buf();
+        docids = Cow::Owned(difference);
+    }
+
     if let Some(facets_docids) = facets_docids {
         let intersection = OpBuilder::new(docids.as_ref(), facets_docids.as_set())
             .intersection()
@@ -273,6 +413,10 @@ where
         before_raw_documents_building.elapsed(),
     );
 
+    if let Some(cursor) = cursor {
+        documents_after_cursor(reader, &criteria, &mut raw_documents, &mut arena, &mapping, index, cursor)?;
+    }
+
     let mut groups = vec![raw_documents.as_mut_slice()];
     let mut key_cache = HashMap::new();
 
@@ -283,7 +427,10 @@ where
     let mut distinct_map = DistinctMap::new(distinct_size);
     let mut distinct_raw_offset = 0;
 
+    let timed_out = || deadline.map_or(false, |deadline| Instant::now() >= deadline);
+
     'criteria: for criterion in criteria.as_ref() {
+
         let tmp_groups = mem::replace(&mut groups, Vec::new());
         let mut buf_distinct = BufferedDistinctMap::new(&mut distinct_map);
         let mut documents_seen = 0;
@@ -297,6 +444,14 @@ where
                 continue;
             }
 
+            // once the search ran out of time the groups are left as they are sorted,
+            // the documents are the best found so far
+            if timed_out() {
+                debug!("search timed out during the {:?} criterion", criterion.name());
+                result.partial = true;
+                break 'criteria;
+            }
+
             let ctx = ContextMut {
                 reader,
                 postings_lists: &mut arena,
@@ -383,42 +538,291 @@ where
     // once we classified the documents related to the current
     // automatons we save that as the next valid result
     let mut seen = BufferedDistinctMap::new(&mut distinct_map);
-    let schema = index.main.schema(reader)?.ok_or(Error::SchemaMissing)?;
 
-    let mut documents = Vec::with_capacity(range.len());
+    let mut selected = Vec::with_capacity(range.len());
     for raw_document in raw_documents.into_iter().skip(distinct_raw_offset) {
+        // the documents not reached before the search ran out of time are evaluated here
         let filter_accepted = match &filter {
-            Some(_) => filter_map.remove(&raw_document.id).unwrap_or_else(|| {
-                error!("error during filtering: expected value for document id {}", &raw_document.id.0);
-                Default::default()
-            }),
+            Some(filter) => filter_map.remove(&raw_document.id).unwrap_or_else(|| (filter)(raw_document.id)),
             None => true,
         };
 
         if filter_accepted {
-            let key = key_cache.remove(&raw_document.id).unwrap_or_else(|| {
-                error!("error during distinct: expected value for document id {}", &raw_document.id.0);
-                Default::default()
-            });
+            let key = key_cache
+                .remove(&raw_document.id)
+                .unwrap_or_else(|| (distinct)(raw_document.id).map(Rc::new));
             let distinct_accepted = match key {
                 Some(key) => seen.register(key),
                 None => seen.register_without_key(),
             };
 
             if distinct_accepted && seen.len() > range.start {
-                documents.push(Document::from_raw(raw_document, &queries_kinds, &arena, searchable_attrs.as_ref(), &schema));
-                if documents.len() == range.len() {
+                selected.push(raw_document);
+                if selected.len() == range.len() {
                     break;
                 }
             }
         }
     }
+
+    let infos = if ranking_infos {
+        documents_ranking_infos(reader, &criteria, &mut selected, &mut arena, &mapping, index)?
+    } else {
+        Vec::new()
+    };
+
+    // the next page starts after the last document of a full page, the documents
+    // of a search that timed out are not all sorted and can't be paginated
+    if !result.partial && selected.len() == range.len() {
+        if let Some(last) = selected.last_mut() {
+            result.cursor = Some(document_cursor(reader, &criteria, last, &mut arena, &mapping, index)?);
+        }
+    }
+
+    let iter = selected.into_iter();
+    let iter = iter.map(|rd| Document::from_raw(rd, &queries_kinds, &arena, searchable_attrs.as_ref(), &schema));
+    let mut documents: Vec<_> = iter.collect();
+
+    for (document, infos) in documents.iter_mut().zip(infos) {
+        document.ranking_infos = infos;
+    }
+
     result.documents = documents;
-    result.nb_hits = docids.len() - filtered_count;
+
+    match nb_hits {
+        NbHits::Estimated => result.nb_hits = docids.len() - filtered_count,
+        nb_hits => {
+            let filter = filter.as_ref().map(|filter| filter as &dyn Fn(DocumentId) -> bool);
+            let distinct = (&distinct as &dyn Fn(DocumentId) -> Option<u64>, distinct_size);
+            let count = count_documents(&docids, filter, Some(distinct), nb_hits.cap());
+            result.nb_hits = count;
+            result.exhaustive_nb_hit = nb_hits.cap().map_or(true, |cap| count < cap);
+        }
+    }
 
     Ok(result)
 }
 
+/// Counts the documents accepted by the filter and the distinct rule,
+/// the count stops once the cap is reached.
+pub fn count_documents(
+    docids: &[DocumentId],
+    filter: Option<&dyn Fn(DocumentId) -> bool>,
+    distinct: Option<(&dyn Fn(DocumentId) -> Option<u64>, usize)>,
+    cap: Option<usize>,
+) -> usize
+{
+    let cap = cap.unwrap_or(usize::MAX);
+    let mut distinct_map = distinct.map(|(_, size)| DistinctMap::new(size));
+    let mut distinct_map = distinct_map.as_mut().map(BufferedDistinctMap::new);
+    let mut count = 0;
+
+    for &docid in docids {
+        if count >= cap {
+            break;
+        }
+
+        if !filter.map_or(true, |filter| (filter)(docid)) {
+            continue;
+        }
+
+        let accepted = match (distinct, &mut distinct_map) {
+            (Some((distinct, _)), Some(distinct_map)) => match (distinct)(docid) {
+                Some(key) => distinct_map.register(key),
+                None => distinct_map.register_without_key(),
+            },
+            _ => true,
+        };
+
+        if accepted {
+            count += 1;
+        }
+    }
+
+    count
+}
+
+/// Keeps only the candidates ranked after the position the cursor points to, the document
+/// it was built from doesn't need to still exist or match the query.
+///
+/// The candidates are compared to the key of the cursor one criterion after the other, a
+/// criterion only prepares the candidates ranked like the cursor by the previous ones.
+/// The candidates ranked like the cursor by all the criteria are ordered by their ids.
+fn documents_after_cursor<'c, 'r, 'tag, 'txn>(
+    reader: &heed::RoTxn<MainT>,
+    criteria: &Criteria<'c>,
+    documents: &mut Vec<RawDocument<'r, 'tag>>,
+    arena: &mut SmallArena<'tag, PostingsListView<'txn>>,
+    query_mapping: &HashMap<QueryId, Range<usize>>,
+    index: &Index,
+    cursor: &Cursor,
+) -> MResult<()>
+{
+    // the cursor was built with other criteria
+    if cursor.key.len() != criteria.as_ref().len() {
+        return Err(Error::InvalidCursor);
+    }
+
+    // the documents after this position are ranked like the cursor so far
+    let mut tied_start = 0;
+
+    for (criterion, key) in criteria.as_ref().iter().zip(&cursor.key) {
+        let ctx = ContextMut {
+            reader,
+            postings_lists: arena,
+            query_mapping,
+            documents_fields_counts_store: index.documents_fields_counts,
+        };
+
+        criterion.prepare(ctx, &mut documents[tied_start..])?;
+
+        let ctx = Context {
+            postings_lists: arena,
+            query_mapping,
+        };
+
+        let orderings: Vec<_> = documents[tied_start..]
+            .iter()
+            .map(|document| criterion.cmp_keys(criterion.key(&ctx, document), *key))
+            .collect();
+
+        let tied = documents.split_off(tied_start);
+        let mut still_tied = Vec::new();
+        for (document, ordering) in tied.into_iter().zip(orderings) {
+            match ordering {
+                cmp::Ordering::Less => (),
+                cmp::Ordering::Equal => still_tied.push(document),
+                cmp::Ordering::Greater => documents.push(document),
+            }
+        }
+
+        tied_start = documents.len();
+        documents.extend(still_tied);
+    }
+
+    let tied = documents.split_off(tied_start);
+    documents.extend(tied.into_iter().filter(|document| document.id > cursor.document_id));
+
+    Ok(())
+}
+
+/// Returns the cursor pointing to the document, holding the keys of all the criteria.
+fn document_cursor<'c, 'r, 'tag, 'txn>(
+    reader: &heed::RoTxn<MainT>,
+    criteria: &Criteria<'c>,
+    document: &mut RawDocument<'r, 'tag>,
+    arena: &mut SmallArena<'tag, PostingsListView<'txn>>,
+    query_mapping: &HashMap<QueryId, Range<usize>>,
+    index: &Index,
+) -> MResult<Cursor>
+{
+    let mut key = Vec::with_capacity(criteria.as_ref().len());
+
+    for criterion in criteria.as_ref() {
+        let ctx = ContextMut {
+            reader,
+            postings_lists: arena,
+            query_mapping,
+            documents_fields_counts_store: index.documents_fields_counts,
+        };
+
+        criterion.prepare(ctx, std::slice::from_mut(document))?;
+
+        let ctx = Context {
+            postings_lists: arena,
+            query_mapping,
+        };
+
+        key.push(criterion.key(&ctx, document));
+    }
+
+    Ok(Cursor::new(key, document.id))
+}
+
+/// Returns the ids of the documents that match the query in one of the searchable attributes.
+fn searchable_attributes_docids(
+    queries: &HashMap<PostingsKey, Cow<Set<DocIndex>>>,
+    searchable_attrs: &ReorderedAttrs,
+) -> SetBuf<DocumentId>
+{
+    let docids = queries
+        .values()
+        .flat_map(|matches| matches.iter())
+        .filter(|di| searchable_attrs.get(di.attribute).is_some())
+        .map(|di| di.document_id)
+        .collect();
+
+    SetBuf::from_dirty(docids)
+}
+
+/// Returns, for each document, the value computed by every criterion to rank it.
+///
+/// The criteria are lazily applied during the sort, a document that was already alone
+/// in its group is not prepared by the following criteria, this is done here.
+fn documents_ranking_infos<'c, 'r, 'tag, 'txn>(
+    reader: &heed::RoTxn<MainT>,
+    criteria: &Criteria<'c>,
+    documents: &mut [RawDocument<'r, 'tag>],
+    arena: &mut SmallArena<'tag, PostingsListView<'txn>>,
+    query_mapping: &HashMap<QueryId, Range<usize>>,
+    index: &Index,
+) -> MResult<Vec<Vec<(String, Number)>>>
+{
+    let mut infos = vec![Vec::new(); documents.len()];
+
+    for criterion in criteria.as_ref() {
+        let ctx = ContextMut {
+            reader,
+            postings_lists: arena,
+            query_mapping,
+            documents_fields_counts_store: index.documents_fields_counts,
+        };
+
+        criterion.prepare(ctx, documents)?;
+
+        let ctx = Context {
+            postings_lists: arena,
+            query_mapping,
+        };
+
+        for (document, infos) in documents.iter().zip(&mut infos) {
+            if let Some(value) = criterion.value(&ctx, document) {
+                infos.push((criterion.name().to_string(), value));
+            }
+        }
+    }
+
+    Ok(infos)
+}
+
+/// Returns, for a document sorted without the criteria, the values of the custom ranking rules.
+pub fn custom_rules_ranking_infos(
+    document_id: DocumentId,
+    schema: &Schema,
+    ranked_map: &RankedMap,
+    ranking_rules: &[RankingRule],
+) -> Vec<(String, Number)> {
+    ranking_rules
+        .iter()
+        .filter_map(|rule| {
+            let (value, _) = custom_rule(schema, rule)?;
+            let value = value.get(document_id, ranked_map)?;
+            Some((rule.to_string(), value))
+        })
+        .collect()
+}
+
+/// Returns the indexed positions of the attributes in which the typos are not allowed.
+fn typo_disabled_attributes(schema: &Schema, typo_tolerance: &TypoTolerance) -> HashSet<u16> {
+    typo_tolerance
+        .disable_on_attributes
+        .iter()
+        .filter_map(|name| schema.id(name))
+        .filter_map(|id| schema.is_searchable(id))
+        .map(|pos| pos.0)
+        .collect()
+}
+
 fn cleanup_bare_matches<'tag, 'txn>(
     arena: &mut SmallArena<'tag, PostingsListView<'txn>>,
     docids: &Set<DocumentId>,
@@ -619,44 +1023,158 @@ pub fn placeholder_document_sort(
     reader: &MainReader,
     ranked_map: &RankedMap
 ) -> MResult<()> {
-    use crate::settings::RankingRule;
-    use std::cmp::Ordering;
-
-    enum SortOrder {
-        Asc,
-        Desc,
-    }
-
     if let Some(ranking_rules) = index.main.ranking_rules(reader)? {
         let schema = index.main.schema(reader)?
             .ok_or(Error::SchemaMissing)?;
+        custom_rules_document_sort(document_ids, &schema, ranked_map, &ranking_rules);
+    }
+    Ok(())
+}
 
-        // Select custom rules from ranking rules, and map them to custom rules
-        // containing a field_id
-        let ranking_rules = ranking_rules.iter().filter_map(|r|
-            match r {
-                RankingRule::Asc(name) => schema.id(name).map(|f| (f, SortOrder::Asc)),
-                RankingRule::Desc(name) => schema.id(name).map(|f| (f, SortOrder::Desc)),
-                _ => None,
-            }).collect::<Vec<_>>();
-
-        document_ids.sort_unstable_by(|a, b| {
-            for (field_id, order) in &ranking_rules {
-                let a_value = ranked_map.get(*a, *field_id);
-                let b_value = ranked_map.get(*b, *field_id);
-                let (a, b) = match order {
-                    SortOrder::Asc => (a_value, b_value),
-                    SortOrder::Desc => (b_value, a_value),
-                };
-                match a.cmp(&b) {
-                    Ordering::Equal => continue,
-                    ordering => return ordering,
-                }
+enum SortOrder {
+    Asc,
+    Desc,
+    /// Ascending, the documents without a value come last.
+    AscMissingLast,
+}
+
+/// The value of a document a custom rule sorts on.
+enum RuleValue {
+    Field(FieldId),
+    GeoDistance(GeoPoint),
+}
+
+impl RuleValue {
+    fn get(&self, document_id: DocumentId, ranked_map: &RankedMap) -> Option<Number> {
+        match self {
+            RuleValue::Field(field_id) => ranked_map.get(document_id, *field_id),
+            RuleValue::GeoDistance(point) => {
+                let distance = ranked_map.geo_point(document_id)?.distance(point);
+                Some(Number::Float(OrderedFloat(distance)))
             }
-            Ordering::Equal
-        });
+        }
+    }
+}
+
+fn custom_rule(schema: &Schema, rule: &RankingRule) -> Option<(RuleValue, SortOrder)> {
+    match rule {
+        RankingRule::Asc(name) => schema.id(name).map(|f| (RuleValue::Field(f), SortOrder::Asc)),
+        RankingRule::Desc(name) => schema.id(name).map(|f| (RuleValue::Field(f), SortOrder::Desc)),
+        RankingRule::GeoDistance(point) => Some((RuleValue::GeoDistance(*point), SortOrder::AscMissingLast)),
+        _ => None,
+    }
+}
+
+/// Selects the custom rules (`asc`, `desc` and `_geoPoint`) found in the given ranking
+/// rules, the other rules are ignored.
+fn custom_rules(schema: &Schema, ranking_rules: &[RankingRule]) -> Vec<(RuleValue, SortOrder)> {
+    ranking_rules.iter().filter_map(|r| custom_rule(schema, r)).collect()
+}
+
+/// Returns the values of the custom rules of a document, in the order of the rules.
+fn custom_rules_key(document_id: DocumentId, ranked_map: &RankedMap, rules: &[(RuleValue, SortOrder)]) -> Vec<Option<Number>> {
+    rules.iter().map(|(value, _)| value.get(document_id, ranked_map)).collect()
+}
+
+/// Compares two documents by the values of the custom rules, the document ids break the ties.
+fn custom_rules_cmp(
+    rules: &[(RuleValue, SortOrder)],
+    (a_key, a_id): (&[Option<Number>], DocumentId),
+    (b_key, b_id): (&[Option<Number>], DocumentId),
+) -> cmp::Ordering {
+    for (i, (_, order)) in rules.iter().enumerate() {
+        let a_value = a_key.get(i).copied().flatten();
+        let b_value = b_key.get(i).copied().flatten();
+        let ordering = match order {
+            SortOrder::Asc => a_value.cmp(&b_value),
+            SortOrder::Desc => b_value.cmp(&a_value),
+            SortOrder::AscMissingLast => {
+                a_value.is_none().cmp(&b_value.is_none()).then(a_value.cmp(&b_value))
+            }
+        };
+        if ordering != cmp::Ordering::Equal {
+            return ordering;
+        }
+    }
+    a_id.cmp(&b_id)
+}
+
+/// Sorts the documents ids according to the custom rules (`asc`, `desc` and `_geoPoint`)
+/// found in the given ranking rules, the other rules are ignored.
+pub fn custom_rules_document_sort(
+    document_ids: &mut [DocumentId],
+    schema: &Schema,
+    ranked_map: &RankedMap,
+    ranking_rules: &[RankingRule],
+) {
+    let rules = custom_rules(schema, ranking_rules);
+
+    let mut keyed: Vec<_> = document_ids
+        .iter()
+        .map(|&id| (custom_rules_key(id, ranked_map, &rules), id))
+        .collect();
+
+    keyed.sort_unstable_by(|(a_key, a_id), (b_key, b_id)| {
+        custom_rules_cmp(&rules, (a_key, *a_id), (b_key, *b_id))
+    });
+
+    for (document_id, (_, id)) in document_ids.iter_mut().zip(keyed) {
+        *document_id = id;
+    }
+}
+
+/// Returns whether the documents ids are sorted according to the custom rules found
+/// in the given ranking rules, the document ids breaking the ties.
+pub fn custom_rules_is_sorted(
+    document_ids: &[DocumentId],
+    schema: &Schema,
+    ranked_map: &RankedMap,
+    ranking_rules: &[RankingRule],
+) -> bool {
+    let rules = custom_rules(schema, ranking_rules);
+
+    let keyed: Vec<_> = document_ids
+        .iter()
+        .map(|&id| (custom_rules_key(id, ranked_map, &rules), id))
+        .collect();
+
+    keyed.windows(2).all(|pair| {
+        let (a_key, a_id) = &pair[0];
+        let (b_key, b_id) = &pair[1];
+        custom_rules_cmp(&rules, (a_key, *a_id), (b_key, *b_id)) != cmp::Ordering::Greater
+    })
+}
+
+/// Returns the cursor pointing to a document sorted according to the custom rules.
+pub fn custom_rules_cursor(
+    document_id: DocumentId,
+    schema: &Schema,
+    ranked_map: &RankedMap,
+    ranking_rules: &[RankingRule],
+) -> Cursor {
+    let rules = custom_rules(schema, ranking_rules);
+    Cursor::new(custom_rules_key(document_id, ranked_map, &rules), document_id)
+}
+
+/// Returns the position, in documents ids sorted according to the custom rules, of the
+/// first document that comes after the cursor.
+pub fn custom_rules_cursor_position(
+    document_ids: &[DocumentId],
+    schema: &Schema,
+    ranked_map: &RankedMap,
+    ranking_rules: &[RankingRule],
+    cursor: &Cursor,
+) -> usize {
+    let rules = custom_rules(schema, ranking_rules);
+    let search = document_ids.binary_search_by(|&id| {
+        let key = custom_rules_key(id, ranked_map, &rules);
+        custom_rules_cmp(&rules, (&key, id), (&cursor.key, cursor.document_id))
+    });
+
+    match search {
+        Ok(position) => position + 1,
+        Err(position) => position,
     }
-    Ok(())
 }
 
 /// For each entry in facet_docids, calculates the number of documents in the intersection with candidate_docids.
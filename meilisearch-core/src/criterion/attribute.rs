@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
 use slice_group_by::GroupBy;
-use crate::{RawDocument, MResult};
+use crate::{Number, RawDocument, MResult};
 use crate::bucket_sort::SimpleMatch;
 use super::{Criterion, Context, ContextMut, prepare_bare_matches};
 
@@ -20,18 +20,31 @@ impl Criterion for Attribute {
     }
 
     fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
-        #[inline]
-        fn sum_of_attribute(matches: &[SimpleMatch]) -> usize {
-            let mut sum_of_attribute = 0;
-            for group in matches.linear_group_by_key(|bm| bm.query_index) {
-                sum_of_attribute += group[0].attribute as usize;
-            }
-            sum_of_attribute
-        }
-
         let lhs = sum_of_attribute(&lhs.processed_matches);
         let rhs = sum_of_attribute(&rhs.processed_matches);
 
         lhs.cmp(&rhs)
     }
+
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        let sum = sum_of_attribute(&document.processed_matches);
+        Some(Number::Unsigned(sum as u64))
+    }
+
+    fn key(&self, ctx: &Context, document: &RawDocument) -> Option<Number> {
+        self.value(ctx, document)
+    }
+
+    fn cmp_keys(&self, lhs: Option<Number>, rhs: Option<Number>) -> Ordering {
+        lhs.cmp(&rhs)
+    }
+}
+
+#[inline]
+fn sum_of_attribute(matches: &[SimpleMatch]) -> usize {
+    let mut sum_of_attribute = 0;
+    for group in matches.linear_group_by_key(|bm| bm.query_index) {
+        sum_of_attribute += group[0].attribute as usize;
+    }
+    sum_of_attribute
 }
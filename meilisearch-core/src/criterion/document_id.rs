@@ -1,5 +1,5 @@
 use std::cmp::Ordering;
-use crate::RawDocument;
+use crate::{Number, RawDocument};
 use super::{Criterion, Context};
 
 pub struct DocumentId;
@@ -13,4 +13,12 @@ impl Criterion for DocumentId {
 
         lhs.cmp(rhs)
     }
+
+    fn key(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        Some(Number::Unsigned(u64::from(document.id.0)))
+    }
+
+    fn cmp_keys(&self, lhs: Option<Number>, rhs: Option<Number>) -> Ordering {
+        lhs.cmp(&rhs)
+    }
 }
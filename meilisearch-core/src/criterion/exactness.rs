@@ -2,7 +2,7 @@ use std::cmp::{Ordering, Reverse};
 use std::collections::hash_map::{HashMap, Entry};
 use meilisearch_schema::IndexedPos;
 use slice_group_by::GroupBy;
-use crate::{RawDocument, MResult};
+use crate::{Number, RawDocument, MResult};
 use crate::bucket_sort::BareMatch;
 use super::{Criterion, Context, ContextMut};
 
@@ -55,17 +55,6 @@ impl Criterion for Exactness {
     }
 
     fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
-        #[inline]
-        fn sum_exact_query_words(matches: &[BareMatch]) -> usize {
-            let mut sum_exact_query_words = 0;
-
-            for group in matches.linear_group_by_key(|bm| bm.query_index) {
-                sum_exact_query_words += group[0].is_exact as usize;
-            }
-
-            sum_exact_query_words
-        }
-
         // does it contains a "one word field"
         lhs.contains_one_word_field.cmp(&rhs.contains_one_word_field).reverse()
         // if not, with document contains the more exact words
@@ -75,4 +64,31 @@ impl Criterion for Exactness {
             lhs.cmp(&rhs).reverse()
         })
     }
+
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        let sum = sum_exact_query_words(&document.bare_matches);
+        Some(Number::Unsigned(sum as u64))
+    }
+
+    fn key(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        // the "one word field" comes first, then the number of exact words
+        let one_word_field = (document.contains_one_word_field as u64) << 32;
+        let sum = sum_exact_query_words(&document.bare_matches) as u64;
+        Some(Number::Unsigned(one_word_field | sum))
+    }
+
+    fn cmp_keys(&self, lhs: Option<Number>, rhs: Option<Number>) -> Ordering {
+        lhs.cmp(&rhs).reverse()
+    }
+}
+
+#[inline]
+fn sum_exact_query_words(matches: &[BareMatch]) -> usize {
+    let mut sum_exact_query_words = 0;
+
+    for group in matches.linear_group_by_key(|bm| bm.query_index) {
+        sum_exact_query_words += group[0].is_exact as usize;
+    }
+
+    sum_exact_query_words
 }
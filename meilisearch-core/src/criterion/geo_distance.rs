@@ -0,0 +1,55 @@
+use std::cmp::Ordering;
+
+use ordered_float::OrderedFloat;
+
+use crate::{GeoPoint, Number, RankedMap, RawDocument};
+use super::{Criterion, Context};
+
+/// Sorts the documents by their distance to a point, the closest first,
+/// the documents without a location come last.
+pub struct GeoDistance<'a> {
+    ranked_map: &'a RankedMap,
+    point: GeoPoint,
+    name: String,
+}
+
+impl<'a> GeoDistance<'a> {
+    pub fn new(ranked_map: &'a RankedMap, point: GeoPoint) -> GeoDistance<'a> {
+        let name = format!("_geoPoint({})", point);
+        GeoDistance { ranked_map, point, name }
+    }
+
+    fn distance(&self, document: &RawDocument) -> Option<OrderedFloat<f64>> {
+        let point = self.ranked_map.geo_point(document.id)?;
+        Some(OrderedFloat(point.distance(&self.point)))
+    }
+}
+
+impl Criterion for GeoDistance<'_> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
+        let lhs = self.distance(lhs).map(Number::Float);
+        let rhs = self.distance(rhs).map(Number::Float);
+        self.cmp_keys(lhs, rhs)
+    }
+
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        self.distance(document).map(Number::Float)
+    }
+
+    fn key(&self, ctx: &Context, document: &RawDocument) -> Option<Number> {
+        self.value(ctx, document)
+    }
+
+    fn cmp_keys(&self, lhs: Option<Number>, rhs: Option<Number>) -> Ordering {
+        match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => lhs.cmp(&rhs),
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
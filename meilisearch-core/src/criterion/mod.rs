@@ -9,7 +9,7 @@ use slice_group_by::GroupBy;
 use crate::bucket_sort::{SimpleMatch, PostingsListView};
 use crate::database::MainT;
 use crate::query_tree::QueryId;
-use crate::{store, RawDocument, MResult};
+use crate::{store, Number, RawDocument, MResult};
 
 mod typo;
 mod words;
@@ -19,6 +19,7 @@ mod words_position;
 mod exactness;
 mod document_id;
 mod sort_by_attr;
+mod geo_distance;
 
 pub use self::typo::Typo;
 pub use self::words::Words;
@@ -28,6 +29,7 @@ pub use self::words_position::WordsPosition;
 pub use self::exactness::Exactness;
 pub use self::document_id::DocumentId;
 pub use self::sort_by_attr::SortByAttr;
+pub use self::geo_distance::GeoDistance;
 
 pub trait Criterion {
     fn name(&self) -> &str;
@@ -58,6 +60,35 @@ pub trait Criterion {
     {
         self.evaluate(ctx, lhs, rhs) == Ordering::Equal
     }
+
+    /// Returns the value this criterion computed to rank the document,
+    /// used to explain why a document is ranked at its position.
+    fn value<'p, 'tag, 'txn, 'q, 'r>(
+        &self,
+        _ctx: &Context<'p, 'tag, 'txn, 'q>,
+        _document: &RawDocument<'r, 'tag>,
+    ) -> Option<Number>
+    {
+        None
+    }
+
+    /// Returns the key this criterion sorts the document on, stored in the cursors to
+    /// find where the next page starts. The document must have been prepared.
+    ///
+    /// A criterion without key is ignored to find the position of a cursor.
+    fn key<'p, 'tag, 'txn, 'q, 'r>(
+        &self,
+        _ctx: &Context<'p, 'tag, 'txn, 'q>,
+        _document: &RawDocument<'r, 'tag>,
+    ) -> Option<Number>
+    {
+        None
+    }
+
+    /// Compares the keys of two documents, in the order `evaluate` sorts the documents.
+    fn cmp_keys(&self, _lhs: Option<Number>, _rhs: Option<Number>) -> Ordering {
+        Ordering::Equal
+    }
 }
 
 pub struct ContextMut<'h, 'p, 'tag, 'txn, 'q> {
@@ -1,7 +1,7 @@
 use std::cmp::{self, Ordering};
 use slice_group_by::GroupBy;
 use crate::bucket_sort::{SimpleMatch};
-use crate::{RawDocument, MResult};
+use crate::{Number, RawDocument, MResult};
 use super::{Criterion, Context, ContextMut, prepare_bare_matches};
 
 const MAX_DISTANCE: u16 = 8;
@@ -22,47 +22,60 @@ impl Criterion for Proximity {
     }
 
     fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
-        fn index_proximity(lhs: u16, rhs: u16) -> u16 {
-            if lhs < rhs {
-                cmp::min(rhs - lhs, MAX_DISTANCE)
-            } else {
-                cmp::min(lhs - rhs, MAX_DISTANCE) + 1
-            }
-        }
+        let lhs = matches_proximity(&lhs.processed_matches);
+        let rhs = matches_proximity(&rhs.processed_matches);
 
-        fn attribute_proximity(lhs: SimpleMatch, rhs: SimpleMatch) -> u16 {
-            if lhs.attribute != rhs.attribute { MAX_DISTANCE }
-            else { index_proximity(lhs.word_index, rhs.word_index) }
-        }
+        lhs.cmp(&rhs)
+    }
 
-        fn min_proximity(lhs: &[SimpleMatch], rhs: &[SimpleMatch]) -> u16 {
-            let mut min_prox = u16::max_value();
-            for a in lhs {
-                for b in rhs {
-                    let prox = attribute_proximity(*a, *b);
-                    min_prox = cmp::min(min_prox, prox);
-                }
-            }
-            min_prox
-        }
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        let proximity = matches_proximity(&document.processed_matches);
+        Some(Number::Unsigned(proximity as u64))
+    }
 
-        fn matches_proximity(matches: &[SimpleMatch],) -> u16 {
-            let mut proximity = 0;
-            let mut iter = matches.linear_group_by_key(|m| m.query_index);
+    fn key(&self, ctx: &Context, document: &RawDocument) -> Option<Number> {
+        self.value(ctx, document)
+    }
 
-            // iterate over groups by windows of size 2
-            let mut last = iter.next();
-            while let (Some(lhs), Some(rhs)) = (last, iter.next()) {
-                proximity += min_proximity(lhs, rhs);
-                last = Some(rhs);
-            }
+    fn cmp_keys(&self, lhs: Option<Number>, rhs: Option<Number>) -> Ordering {
+        lhs.cmp(&rhs)
+    }
+}
 
-            proximity
+fn index_proximity(lhs: u16, rhs: u16) -> u16 {
+    if lhs < rhs {
+        cmp::min(rhs - lhs, MAX_DISTANCE)
+    } else {
+        cmp::min(lhs - rhs, MAX_DISTANCE) + 1
+    }
+}
+
+fn attribute_proximity(lhs: SimpleMatch, rhs: SimpleMatch) -> u16 {
+    if lhs.attribute != rhs.attribute { MAX_DISTANCE }
+    else { index_proximity(lhs.word_index, rhs.word_index) }
+}
+
+fn min_proximity(lhs: &[SimpleMatch], rhs: &[SimpleMatch]) -> u16 {
+    let mut min_prox = u16::max_value();
+    for a in lhs {
+        for b in rhs {
+            let prox = attribute_proximity(*a, *b);
+            min_prox = cmp::min(min_prox, prox);
         }
+    }
+    min_prox
+}
 
-        let lhs = matches_proximity(&lhs.processed_matches);
-        let rhs = matches_proximity(&rhs.processed_matches);
+fn matches_proximity(matches: &[SimpleMatch],) -> u16 {
+    let mut proximity = 0;
+    let mut iter = matches.linear_group_by_key(|m| m.query_index);
 
-        lhs.cmp(&rhs)
+    // iterate over groups by windows of size 2
+    let mut last = iter.next();
+    while let (Some(lhs), Some(rhs)) = (last, iter.next()) {
+        proximity += min_proximity(lhs, rhs);
+        last = Some(rhs);
     }
+
+    proximity
 }
@@ -2,7 +2,7 @@ use std::cmp::Ordering;
 use std::error::Error;
 use std::fmt;
 use meilisearch_schema::{Schema, FieldId};
-use crate::{RankedMap, RawDocument};
+use crate::{Number, RankedMap, RawDocument};
 use super::{Criterion, Context};
 
 /// An helper struct that permit to sort documents by
@@ -43,6 +43,7 @@ pub struct SortByAttr<'a> {
     ranked_map: &'a RankedMap,
     field_id: FieldId,
     reversed: bool,
+    name: String,
 }
 
 impl<'a> SortByAttr<'a> {
@@ -77,23 +78,41 @@ impl<'a> SortByAttr<'a> {
             return Err(SortByAttrError::AttributeNotRegisteredForRanking);
         }
 
+        let name = if reversed {
+            format!("desc({})", attr_name)
+        } else {
+            format!("asc({})", attr_name)
+        };
+
         Ok(SortByAttr {
             ranked_map,
             field_id,
             reversed,
+            name,
         })
     }
 }
 
 impl Criterion for SortByAttr<'_> {
     fn name(&self) -> &str {
-        "sort by attribute"
+        &self.name
     }
 
     fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
         let lhs = self.ranked_map.get(lhs.id, self.field_id);
         let rhs = self.ranked_map.get(rhs.id, self.field_id);
+        self.cmp_keys(lhs, rhs)
+    }
+
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        self.ranked_map.get(document.id, self.field_id)
+    }
+
+    fn key(&self, ctx: &Context, document: &RawDocument) -> Option<Number> {
+        self.value(ctx, document)
+    }
 
+    fn cmp_keys(&self, lhs: Option<Number>, rhs: Option<Number>) -> Ordering {
         match (lhs, rhs) {
             (Some(lhs), Some(rhs)) => {
                 let order = lhs.cmp(&rhs);
@@ -1,5 +1,5 @@
 use std::cmp::Ordering;
-use crate::{RawDocument, MResult};
+use crate::{Number, RawDocument, MResult};
 use super::{Criterion, Context, ContextMut, prepare_query_distances};
 
 pub struct Typo;
@@ -18,39 +18,52 @@ impl Criterion for Typo {
     }
 
     fn evaluate(&self, _ctx: &Context, lhs: &RawDocument, rhs: &RawDocument) -> Ordering {
-        // This function is a wrong logarithmic 10 function.
-        // It is safe to panic on input number higher than 3,
-        // the number of typos is never bigger than that.
-        #[inline]
-        #[allow(clippy::approx_constant)]
-        fn custom_log10(n: u8) -> f32 {
-            match n {
-                0 => 0.0,     // log(1)
-                1 => 0.30102, // log(2)
-                2 => 0.47712, // log(3)
-                3 => 0.60205, // log(4)
-                _ => panic!("invalid number"),
-            }
-        }
-
-        #[inline]
-        fn compute_typos(distances: &[Option<u8>]) -> usize {
-            let mut number_words: usize = 0;
-            let mut sum_typos = 0.0;
+        let lhs = compute_typos(&lhs.processed_distances);
+        let rhs = compute_typos(&rhs.processed_distances);
 
-            for distance in distances {
-                if let Some(distance) = distance {
-                    sum_typos += custom_log10(*distance);
-                    number_words += 1;
-                }
-            }
+        lhs.cmp(&rhs).reverse()
+    }
 
-            (number_words as f32 / (sum_typos + 1.0) * 1000.0) as usize
-        }
+    fn value(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        let typos = document.processed_distances.iter().flatten().map(|d| *d as u64).sum();
+        Some(Number::Unsigned(typos))
+    }
 
-        let lhs = compute_typos(&lhs.processed_distances);
-        let rhs = compute_typos(&rhs.processed_distances);
+    fn key(&self, _ctx: &Context, document: &RawDocument) -> Option<Number> {
+        Some(Number::Unsigned(compute_typos(&document.processed_distances) as u64))
+    }
 
+    fn cmp_keys(&self, lhs: Option<Number>, rhs: Option<Number>) -> Ordering {
         lhs.cmp(&rhs).reverse()
     }
 }
+
+// This function is a wrong logarithmic 10 function.
+// It is safe to panic on input number higher than 3,
+// the number of typos is never bigger than that.
+#[inline]
+#[allow(clippy::approx_constant)]
+fn custom_log10(n: u8) -> f32 {
+    match n {
+        0 => 0.0,     // log(1)
+        1 => 0.30102, // log(2)
+        2 => 0.47712, // log(3)
+        3 => 0.60205, // log(4)
+        _ => panic!("invalid number"),
+    }
+}
+
+#[inline]
+fn compute_typos(distances: &[Option<u8>]) -> usize {
+    let mut number_words: usize = 0;
+    let mut sum_typos = 0.0;
+
+    for distance in distances {
+        if let Some(distance) = distance {
+            sum_typos += custom_log10(*distance);
+            number_words += 1;
+        }
+    }
+
+    (number_words as f32 / (sum_typos + 1.0) * 1000.0) as usize
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{DocumentId, Number};
+
+/// The position of a document in a list of sorted results, the following page
+/// of results starts right after this document.
+///
+/// A placeholder search is sorted by the custom ranking rules, the key holds the values
+/// of these rules for the document. A query search is sorted by the criteria, the key holds
+/// the key of each criterion for the document. In both cases the document id breaks the
+/// ties, the document doesn't need to exist anymore for the next page to be found.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cursor {
+    pub key: Vec<Option<Number>>,
+    pub document_id: DocumentId,
+}
+
+impl Cursor {
+    pub fn new(key: Vec<Option<Number>>, document_id: DocumentId) -> Cursor {
+        Cursor { key, document_id }
+    }
+}
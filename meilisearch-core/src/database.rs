@@ -280,6 +280,10 @@ impl Database {
                 }
             };
 
+            let mut writer = env.typed_write_txn::<MainT>()?;
+            update::resort_document_ids_cache(&mut writer, &index)?;
+            writer.commit()?;
+
             let env_clone = env.clone();
             let update_env_clone = update_env.clone();
             let index_clone = index.clone();
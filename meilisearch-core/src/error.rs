@@ -22,6 +22,7 @@ pub enum Error {
     Fst(fst::Error),
     Heed(heed::Error),
     IndexAlreadyExists,
+    InvalidCursor,
     Io(io::Error),
     MaxFieldsLimitExceeded,
     MissingDocumentId,
@@ -42,6 +43,7 @@ impl ErrorCode for Error {
             FacetError(_) => Code::Facet,
             FilterParseError(_) => Code::Filter,
             IndexAlreadyExists => Code::IndexAlreadyExists,
+            InvalidCursor => Code::BadParameter,
             MissingPrimaryKey => Code::MissingPrimaryKey,
             MissingDocumentId => Code::MissingDocumentId,
             MaxFieldsLimitExceeded => Code::MaxFieldsLimitExceeded,
@@ -80,6 +82,8 @@ impl From<PestError<Rule>> for Error {
                 Rule::eq => "field = value",
                 Rule::leq => "field <= value",
                 Rule::geq => "field >= value",
+                Rule::geo_radius => "_geoRadius(lat, lng, meters)",
+                Rule::geo_bounding_box => "_geoBoundingBox([lat, lng], [lat, lng])",
                 Rule::key => "key",
                 _ => "other",
             };
@@ -150,6 +154,7 @@ impl fmt::Display for Error {
             Fst(e) => write!(f, "fst error; {}", e),
             Heed(e) => write!(f, "heed error; {}", e),
             IndexAlreadyExists => write!(f, "index already exists"),
+            InvalidCursor => write!(f, "the cursor doesn't match the ranking rules of the search"),
             Io(e) => write!(f, "{}", e),
             MaxFieldsLimitExceeded => write!(f, "maximum number of fields in a document exceeded"),
             MissingDocumentId => write!(f, "document id is missing"),
@@ -18,7 +18,7 @@ use crate::store::BEU16;
 
 /// Data structure used to represent a boolean expression in the form of nested arrays.
 /// Values in the outer array are and-ed together, values in the inner arrays are or-ed together.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FacetFilter(Vec<Either<Vec<FacetKey>, FacetKey>>);
 
 impl Deref for FacetFilter {
@@ -77,7 +77,7 @@ impl FacetFilter {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 #[repr(C)]
 pub struct FacetKey(FieldId, String);
 
@@ -2,7 +2,7 @@ use std::str::FromStr;
 use std::cmp::Ordering;
 
 use crate::error::Error;
-use crate::{store::Index, DocumentId, MainT};
+use crate::{store::Index, DocumentId, GeoPoint, MainT, GEO_FIELD};
 use heed::RoTxn;
 use meilisearch_schema::{FieldId, Schema};
 use pest::error::{Error as PestError, ErrorVariant};
@@ -208,6 +208,82 @@ impl<'a> Condition<'a> {
     }
 }
 
+/// A condition on the location of the documents, stored in their `_geo` attribute.
+#[derive(Debug)]
+pub enum GeoCondition {
+    Radius { field: Option<FieldId>, point: GeoPoint, meters: f64 },
+    BoundingBox { field: Option<FieldId>, top_right: GeoPoint, bottom_left: GeoPoint },
+}
+
+fn parse_coordinates(pair: Pair<Rule>) -> Result<Vec<f64>, Error> {
+    pair.into_inner()
+        .map(|value| {
+            value.as_str().parse::<f64>().map_err(|_| PestError::new_from_span(
+                ErrorVariant::CustomError {
+                    message: format!("`{}` is not a valid number", value.as_str()),
+                },
+                value.as_span()).into())
+        })
+        .collect()
+}
+
+fn parse_geo_point(pair: &Pair<Rule>, lat: f64, lng: f64) -> Result<GeoPoint, Error> {
+    GeoPoint::new(lat, lng).ok_or_else(|| PestError::new_from_span(
+        ErrorVariant::CustomError {
+            message: format!("`{}, {}` is not a valid geo point", lat, lng),
+        },
+        pair.as_span()).into())
+}
+
+impl GeoCondition {
+    /// Parses `_geoRadius(lat, lng, meters)`.
+    pub fn radius(item: Pair<Rule>, schema: &Schema) -> Result<Self, Error> {
+        let field = schema.id(GEO_FIELD);
+        let coordinates = parse_coordinates(item.clone())?;
+        let point = parse_geo_point(&item, coordinates[0], coordinates[1])?;
+        Ok(GeoCondition::Radius { field, point, meters: coordinates[2] })
+    }
+
+    /// Parses `_geoBoundingBox([lat, lng], [lat, lng])`, the top right corner of the box
+    /// followed by its bottom left corner.
+    pub fn bounding_box(item: Pair<Rule>, schema: &Schema) -> Result<Self, Error> {
+        let field = schema.id(GEO_FIELD);
+        let coordinates = parse_coordinates(item.clone())?;
+        let top_right = parse_geo_point(&item, coordinates[0], coordinates[1])?;
+        let bottom_left = parse_geo_point(&item, coordinates[2], coordinates[3])?;
+        Ok(GeoCondition::BoundingBox { field, top_right, bottom_left })
+    }
+
+    pub fn test(
+        &self,
+        reader: &RoTxn<MainT>,
+        index: &Index,
+        document_id: DocumentId,
+    ) -> Result<bool, Error> {
+        let field = match self {
+            GeoCondition::Radius { field, .. } | GeoCondition::BoundingBox { field, .. } => *field,
+        };
+
+        // documents without a valid location never match
+        let point = match field {
+            Some(field) => index
+                .document_attribute::<Value>(reader, document_id, field)?
+                .and_then(|value| GeoPoint::from_value(&value)),
+            None => None,
+        };
+
+        match (self, point) {
+            (GeoCondition::Radius { point, meters, .. }, Some(location)) => {
+                Ok(location.distance(point) <= *meters)
+            }
+            (GeoCondition::BoundingBox { top_right, bottom_left, .. }, Some(location)) => {
+                Ok(location.is_in_bounding_box(top_right, bottom_left))
+            }
+            (_, None) => Ok(false),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -5,7 +5,7 @@ pub(crate) use parser::Rule;
 
 use std::ops::Not;
 
-use condition::Condition;
+use condition::{Condition, GeoCondition};
 use crate::error::Error;
 use crate::{DocumentId, MainT, store::Index};
 use heed::RoTxn;
@@ -19,6 +19,7 @@ type FilterResult<'a> = Result<Filter<'a>, Error>;
 #[derive(Debug)]
 pub enum Filter<'a> {
     Condition(Condition<'a>),
+    Geo(GeoCondition),
     Or(Box<Self>, Box<Self>),
     And(Box<Self>, Box<Self>),
     Not(Box<Self>),
@@ -39,6 +40,7 @@ impl<'a> Filter<'a> {
         use Filter::*;
         match self {
             Condition(c) => c.test(reader, index, document_id),
+            Geo(c) => c.test(reader, index, document_id),
             Or(lhs, rhs) => Ok(
                 lhs.test(reader, index, document_id)? || rhs.test(reader, index, document_id)?
             ),
@@ -59,6 +61,8 @@ impl<'a> Filter<'a> {
                 Rule::neq => Ok(Filter::Condition(Condition::neq(pair, schema)?)),
                 Rule::geq => Ok(Filter::Condition(Condition::geq(pair, schema)?)),
                 Rule::leq => Ok(Filter::Condition(Condition::leq(pair, schema)?)),
+                Rule::geo_radius => Ok(Filter::Geo(GeoCondition::radius(pair, schema)?)),
+                Rule::geo_bounding_box => Ok(Filter::Geo(GeoCondition::bounding_box(pair, schema)?)),
                 Rule::prgm => Self::build(pair.into_inner(), schema),
                 Rule::term => Self::build(pair.into_inner(), schema),
                 Rule::not => Ok(Filter::Not(Box::new(Self::build(
@@ -97,6 +101,8 @@ mod test {
         assert!(FilterParser::parse(Rule::prgm, "hello world=1").is_err());
         assert!(FilterParser::parse(Rule::prgm, "").is_err());
         assert!(FilterParser::parse(Rule::prgm, r#"((((((hello=world)))))"#).is_err());
+        assert!(FilterParser::parse(Rule::prgm, "_geoRadius(1, 2)").is_err());
+        assert!(FilterParser::parse(Rule::prgm, "_geoBoundingBox(1, 2, 3, 4)").is_err());
     }
 
     #[test]
@@ -123,5 +129,7 @@ mod test {
         assert!(FilterParser::parse(Rule::prgm, r#"'foo bar' <= 10"#).is_ok());
         assert!(FilterParser::parse(Rule::prgm, r#"'foo bar' != 10"#).is_ok());
         assert!(FilterParser::parse(Rule::prgm, r#"bar != 10"#).is_ok());
+        assert!(FilterParser::parse(Rule::prgm, "_geoRadius(48.85, 2.35, 2000)").is_ok());
+        assert!(FilterParser::parse(Rule::prgm, "_geoBoundingBox([48.9, 2.4], [48.8, -2.3]) AND field=1").is_ok());
     }
 }
@@ -0,0 +1,136 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The reserved attribute holding the location of a document, an object with
+/// a `lat` and a `lng` field.
+pub const GEO_FIELD: &str = "_geo";
+
+/// The mean radius of the earth, in meters.
+const EARTH_RADIUS: f64 = 6_371_000.0;
+
+/// A location on earth, its latitude and longitude are expressed in degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+impl GeoPoint {
+    pub fn new(lat: f64, lng: f64) -> Option<GeoPoint> {
+        let valid = (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng);
+        if valid { Some(GeoPoint { lat, lng }) } else { None }
+    }
+
+    /// Reads the location stored in the `_geo` attribute of a document, the coordinates
+    /// can be numbers or strings containing numbers.
+    pub fn from_value(value: &Value) -> Option<GeoPoint> {
+        fn coordinate(value: Option<&Value>) -> Option<f64> {
+            match value? {
+                Value::Number(number) => number.as_f64(),
+                Value::String(string) => string.trim().parse().ok(),
+                _ => None,
+            }
+        }
+
+        let object = value.as_object()?;
+        GeoPoint::new(coordinate(object.get("lat"))?, coordinate(object.get("lng"))?)
+    }
+
+    /// Returns the great-circle distance between two locations, in meters.
+    pub fn distance(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let delta_lat = (other.lat - self.lat).to_radians();
+        let delta_lng = (other.lng - self.lng).to_radians();
+
+        let a = (delta_lat / 2.0).sin().powi(2)
+            + lat1.cos() * lat2.cos() * (delta_lng / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS * a.sqrt().asin()
+    }
+
+    /// Returns whether the location is inside the box delimited by its top right and bottom
+    /// left corners, the box can cross the antimeridian.
+    pub fn is_in_bounding_box(&self, top_right: &GeoPoint, bottom_left: &GeoPoint) -> bool {
+        let in_lat = bottom_left.lat <= self.lat && self.lat <= top_right.lat;
+        let in_lng = if bottom_left.lng <= top_right.lng {
+            bottom_left.lng <= self.lng && self.lng <= top_right.lng
+        } else {
+            bottom_left.lng <= self.lng || self.lng <= top_right.lng
+        };
+        in_lat && in_lng
+    }
+}
+
+impl fmt::Display for GeoPoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}, {}", self.lat, self.lng)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseGeoPointError;
+
+impl fmt::Display for ParseGeoPointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid geo point, expected a latitude and a longitude separated by a comma")
+    }
+}
+
+impl FromStr for GeoPoint {
+    type Err = ParseGeoPointError;
+
+    /// Parses a `lat, lng` pair.
+    fn from_str(s: &str) -> Result<GeoPoint, ParseGeoPointError> {
+        let mut iter = s.split(',').map(|c| c.trim().parse::<f64>());
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(Ok(lat)), Some(Ok(lng)), None) => GeoPoint::new(lat, lng).ok_or(ParseGeoPointError),
+            _ => Err(ParseGeoPointError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn distance() {
+        let paris = GeoPoint::new(48.8566, 2.3522).unwrap();
+        let london = GeoPoint::new(51.5074, -0.1278).unwrap();
+
+        let distance = paris.distance(&london);
+        assert!((distance - 343_500.0).abs() < 1_000.0, "{}", distance);
+        assert_eq!(paris.distance(&paris), 0.0);
+    }
+
+    #[test]
+    fn bounding_box() {
+        let point = GeoPoint::new(10.0, 179.0).unwrap();
+
+        let top_right = GeoPoint::new(20.0, 180.0).unwrap();
+        let bottom_left = GeoPoint::new(0.0, 170.0).unwrap();
+        assert!(point.is_in_bounding_box(&top_right, &bottom_left));
+
+        // the box crosses the antimeridian
+        let top_right = GeoPoint::new(20.0, -170.0).unwrap();
+        assert!(point.is_in_bounding_box(&top_right, &bottom_left));
+
+        let bottom_left = GeoPoint::new(15.0, 170.0).unwrap();
+        assert!(!point.is_in_bounding_box(&top_right, &bottom_left));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(GeoPoint::from_value(&json!({ "lat": 1.5, "lng": "-2" })), GeoPoint::new(1.5, -2.0));
+        assert_eq!(GeoPoint::from_value(&json!({ "lat": 91, "lng": 0 })), None);
+        assert_eq!(GeoPoint::from_value(&json!([1, 2])), None);
+
+        assert_eq!("48.8, 2.3".parse(), Ok(GeoPoint { lat: 48.8, lng: 2.3 }));
+        assert_eq!("48.8".parse::<GeoPoint>(), Err(ParseGeoPointError));
+        assert_eq!("48.8, 2.3, 1".parse::<GeoPoint>(), Err(ParseGeoPointError));
+    }
+}
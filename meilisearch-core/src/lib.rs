@@ -8,10 +8,12 @@ extern crate pest_derive;
 
 mod automaton;
 mod bucket_sort;
+mod cursor;
 mod database;
 mod distinct_map;
 mod error;
 mod filters;
+mod geo;
 mod levenshtein;
 mod number;
 mod query_builder;
@@ -20,6 +22,7 @@ mod query_words_mapper;
 mod ranked_map;
 mod raw_document;
 mod reordered_attrs;
+mod vector;
 pub mod criterion;
 pub mod facets;
 pub mod raw_indexer;
@@ -28,13 +31,18 @@ pub mod settings;
 pub mod store;
 pub mod update;
 
+pub use self::bucket_sort::NbHits;
+pub use self::cursor::Cursor;
 pub use self::database::{BoxUpdateFn, Database, DatabaseOptions, MainT, UpdateT, MainWriter, MainReader, UpdateWriter, UpdateReader};
 pub use self::error::{Error, HeedError, FstError, MResult, pest_error, FacetError};
 pub use self::filters::Filter;
+pub use self::geo::{GeoPoint, ParseGeoPointError, GEO_FIELD};
 pub use self::number::{Number, ParseNumberError};
+pub use self::query_tree::MatchingStrategy;
 pub use self::ranked_map::RankedMap;
 pub use self::raw_document::RawDocument;
 pub use self::store::Index;
+pub use self::vector::{cosine_similarity, vector_from_value, VECTOR_FIELD};
 pub use self::update::{EnqueuedUpdateResult, ProcessedUpdateResult, UpdateStatus, UpdateType};
 pub use meilisearch_types::{DocIndex, DocumentId, Highlight};
 pub use meilisearch_schema::Schema;
@@ -42,6 +50,7 @@ pub use query_words_mapper::QueryWordsMapper;
 
 use compact_arena::SmallArena;
 use log::{error, trace};
+use slice_group_by::GroupBy;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::convert::TryFrom;
@@ -58,6 +67,9 @@ type FstMapCow<'a> = fst::Map<Cow<'a, [u8]>>;
 pub struct Document {
     pub id: DocumentId,
     pub highlights: Vec<Highlight>,
+    /// The values computed by each criterion to rank this document,
+    /// only filled when the ranking infos are requested.
+    pub ranking_infos: Vec<(String, Number)>,
 
     #[cfg(test)]
     pub matches: Vec<crate::bucket_sort::SimpleMatch>,
@@ -78,7 +90,23 @@ fn highlights_from_raw_document<'a, 'tag, 'txn>(
         let input = postings_list.input();
         let kind = &queries_kinds.get(&bm.query_index);
 
-        for di in postings_list.iter() {
+        // The words of a phrase follow each other, they are merged
+        // to highlight the phrase as a whole.
+        let matches: Cow<[DocIndex]> = match kind {
+            Some(QueryKind::Phrase(_)) => postings_list
+                .linear_group_by(|a, b| {
+                    a.attribute == b.attribute && (a.word_index as u32) + 1 == b.word_index as u32
+                })
+                .map(|group| {
+                    let (first, last) = (group[0], group[group.len() - 1]);
+                    let char_length = (last.char_index + last.char_length).saturating_sub(first.char_index);
+                    DocIndex { char_length, ..first }
+                })
+                .collect(),
+            _ => Cow::Borrowed(&postings_list[..]),
+        };
+
+        for di in matches.iter() {
             let covered_area = match kind {
                 Some(QueryKind::NonTolerant(query)) | Some(QueryKind::Tolerant(query)) => {
                     let len = if query.len() > input.len() {
@@ -120,12 +148,12 @@ fn highlights_from_raw_document<'a, 'tag, 'txn>(
 impl Document {
     #[cfg(not(test))]
     pub fn from_highlights(id: DocumentId, highlights: &[Highlight]) -> Document {
-        Document { id, highlights: highlights.to_owned() }
+        Document { id, highlights: highlights.to_owned(), ranking_infos: Vec::new() }
     }
 
     #[cfg(test)]
     pub fn from_highlights(id: DocumentId, highlights: &[Highlight]) -> Document {
-        Document { id, highlights: highlights.to_owned(), matches: Vec::new() }
+        Document { id, highlights: highlights.to_owned(), ranking_infos: Vec::new(), matches: Vec::new() }
     }
 
     #[cfg(not(test))]
@@ -145,7 +173,7 @@ impl Document {
             schema,
         );
 
-        Document { id: raw_document.id, highlights }
+        Document { id: raw_document.id, highlights, ranking_infos: Vec::new() }
     }
 
     #[cfg(test)]
@@ -186,7 +214,7 @@ impl Document {
         }
         matches.sort_unstable();
 
-        Document { id: raw_document.id, highlights, matches }
+        Document { id: raw_document.id, highlights, ranking_infos: Vec::new(), matches }
     }
 }
 
@@ -1,7 +1,8 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::cmp::{self, Ordering};
+use std::collections::{HashMap, HashSet};
 use std::ops::{Deref, Range};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use either::Either;
 use sdset::{SetOperation, SetBuf, Set};
@@ -9,12 +10,21 @@ use sdset::{SetOperation, SetBuf, Set};
 use meilisearch_schema::FieldId;
 
 use crate::bucket_sort::{bucket_sort, bucket_sort_with_distinct, SortResult, placeholder_document_sort, facet_count};
+use crate::bucket_sort::{count_documents, NbHits};
+use crate::query_tree::MatchingStrategy;
+use crate::bucket_sort::{custom_rules_document_sort, custom_rules_ranking_infos};
+use crate::bucket_sort::{custom_rules_cursor, custom_rules_cursor_position};
 use crate::database::MainT;
 use crate::facets::FacetFilter;
+use crate::settings::RankingRule;
 use crate::distinct_map::{DistinctMap, BufferedDistinctMap};
-use crate::Document;
+use crate::{cosine_similarity, Cursor, Document};
 use crate::{criterion::Criteria, DocumentId};
-use crate::{reordered_attrs::ReorderedAttrs, store, MResult, MainReader};
+use crate::{reordered_attrs::ReorderedAttrs, store, Error, MResult, MainReader};
+
+/// The number of documents ranked by the keywords that are blended with the vector
+/// similarities, the documents ranked further only count for their similarity.
+const HYBRID_KEYWORD_WINDOW: usize = 1000;
 
 pub struct QueryBuilder<'c, 'f, 'd, 'i> {
     criteria: Criteria<'c>,
@@ -25,6 +35,15 @@ pub struct QueryBuilder<'c, 'f, 'd, 'i> {
     index: &'i store::Index,
     facet_filter: Option<FacetFilter>,
     facets: Option<Vec<(FieldId, String)>>,
+    sort: Option<Vec<RankingRule>>,
+    ranking_infos: bool,
+    cursor: Option<Cursor>,
+    nb_hits: NbHits,
+    matching_strategy: MatchingStrategy,
+    exact_words: bool,
+    vector: Option<Vec<f32>>,
+    semantic_ratio: f32,
+    pinned_documents: Vec<(usize, DocumentId)>,
 }
 
 impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
@@ -42,6 +61,56 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
         self.facets = facets;
     }
 
+    /// sets the custom rules used to sort the documents of a placeholder query,
+    /// in place of the custom rules of the index
+    pub fn set_sort(&mut self, sort: Option<Vec<RankingRule>>) {
+        self.sort = sort;
+    }
+
+    /// sets whether the values computed by each criterion must be returned with the documents
+    pub fn set_ranking_infos(&mut self, ranking_infos: bool) {
+        self.ranking_infos = ranking_infos;
+    }
+
+    /// sets the cursor returned with a previous page, the requested range then starts
+    /// right after the document it points to
+    pub fn set_cursor(&mut self, cursor: Option<Cursor>) {
+        self.cursor = cursor;
+    }
+
+    /// sets how the number of matching documents is computed
+    pub fn set_nb_hits(&mut self, nb_hits: NbHits) {
+        self.nb_hits = nb_hits;
+    }
+
+    /// sets which words of the query the documents can miss
+    pub fn set_matching_strategy(&mut self, matching_strategy: MatchingStrategy) {
+        self.matching_strategy = matching_strategy;
+    }
+
+    /// sets whether the words of the query are searched as they are written, without typos,
+    /// prefixes nor synonyms
+    pub fn set_exact_words(&mut self, exact_words: bool) {
+        self.exact_words = exact_words;
+    }
+
+    /// sets the embedding the documents are ranked against, by cosine similarity
+    pub fn set_vector(&mut self, vector: Option<Vec<f32>>) {
+        self.vector = vector;
+    }
+
+    /// sets the weight of the vector similarity against the keyword ranking when both a query
+    /// and a vector are given, from 0 (keywords only) to 1 (vector only)
+    pub fn set_semantic_ratio(&mut self, semantic_ratio: f32) {
+        self.semantic_ratio = semantic_ratio;
+    }
+
+    /// sets the documents placed at the given positions of the results, the ones that don't
+    /// match the filters of the search are ignored, the others are not ranked as well
+    pub fn set_pinned_documents(&mut self, pinned_documents: Vec<(usize, DocumentId)>) {
+        self.pinned_documents = pinned_documents;
+    }
+
     pub fn with_criteria(index: &'i store::Index, criteria: Criteria<'c>) -> Self {
         QueryBuilder {
             criteria,
@@ -52,6 +121,15 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
             index,
             facet_filter: None,
             facets: None,
+            sort: None,
+            ranking_infos: false,
+            cursor: None,
+            nb_hits: NbHits::default(),
+            matching_strategy: MatchingStrategy::default(),
+            exact_words: false,
+            vector: None,
+            semantic_ratio: 0.5,
+            pinned_documents: Vec::new(),
         }
     }
 
@@ -124,6 +202,9 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
     }
 
     fn standard_query(self, reader: &MainReader, query: &str, range: Range<usize>) -> MResult<SortResult> {
+        // the time budget includes the preparation of the facets
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+
         let facets_docids = match self.facets_docids(reader)? {
             Some(ids) if ids.is_empty() => return Ok(SortResult::default()),
             other => other
@@ -145,6 +226,12 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
                 distinct_size,
                 self.criteria,
                 self.searchable_attrs,
+                self.ranking_infos,
+                deadline,
+                self.cursor.as_ref(),
+                self.nb_hits,
+                self.matching_strategy,
+                self.exact_words,
                 self.index,
             ),
             None => bucket_sort(
@@ -156,12 +243,128 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
                 self.filter,
                 self.criteria,
                 self.searchable_attrs,
+                self.ranking_infos,
+                deadline,
+                self.cursor.as_ref(),
+                self.nb_hits,
+                self.matching_strategy,
+                self.exact_words,
                 self.index,
             ),
         }
     }
 
+    /// returns the candidates having an embedding, sorted by decreasing similarity with the
+    /// given vector, every embedding is compared for now
+    fn vector_scores(&self, reader: &MainReader, vector: &[f32]) -> MResult<Vec<(DocumentId, f32)>> {
+        let facets_docids = self.facets_docids(reader)?;
+
+        let mut scores = Vec::new();
+        for result in self.index.vectors.iter(reader)? {
+            let (docid, document_vector) = result?;
+            if facets_docids.as_ref().map_or(false, |ids| ids.binary_search(&docid).is_err()) {
+                continue;
+            }
+            if !self.filter.as_ref().map_or(true, |filter| (filter)(docid)) {
+                continue;
+            }
+            if let Some(similarity) = cosine_similarity(vector, &document_vector) {
+                scores.push((docid, similarity));
+            }
+        }
+
+        scores.sort_unstable_by(|(a, sa), (b, sb)| sb.partial_cmp(sa).unwrap_or(Ordering::Equal).then(a.cmp(b)));
+        Ok(scores)
+    }
+
+    fn vector_query(self, reader: &MainReader, vector: &[f32], range: Range<usize>) -> MResult<SortResult> {
+        let scores = self.vector_scores(reader, vector)?;
+
+        let documents = scores.iter().map(|(id, _)| Document::from_highlights(*id, &[])).collect();
+        let distinct = self.distinct.as_ref().map(|(distinct, size)| (distinct.as_ref(), *size));
+        let (documents, nb_hits) = paginate_scored_documents(documents, range, distinct);
+
+        let mut result = SortResult { documents, nb_hits, exhaustive_nb_hit: true, ..SortResult::default() };
+
+        if let Some(f) = self.facet_count_docids(reader)? {
+            let docids = SetBuf::from_dirty(scores.into_iter().map(|(id, _)| id).collect());
+            result.exhaustive_facets_count = Some(true);
+            result.facets = Some(facet_count(f, &docids));
+        }
+
+        Ok(result)
+    }
+
+    /// blends the keyword ranking of the query with the similarity of the embeddings, a document
+    /// found by only one of them gets the lowest score of the other one
+    fn hybrid_query(
+        mut self,
+        reader: &MainReader,
+        query: &str,
+        vector: &[f32],
+        range: Range<usize>,
+    ) -> MResult<SortResult> {
+        let semantic_ratio = self.semantic_ratio;
+        let similarities = self.vector_scores(reader, vector)?;
+        let facet_count_docids = self.facet_count_docids(reader)?;
+
+        // the distinct rule and the facets apply to the blended documents,
+        // the pages are only built from the offset
+        let distinct = self.distinct.take();
+        self.facets = None;
+        self.cursor = None;
+
+        let window = cmp::max(range.end, HYBRID_KEYWORD_WINDOW);
+        let keyword = self.standard_query(reader, query, 0..window)?;
+        let keyword_len = keyword.documents.len();
+
+        let mut scored = Vec::with_capacity(keyword_len + similarities.len());
+        let semantic_scores: HashMap<_, _> = similarities.iter().map(|(id, s)| (*id, (s + 1.0) / 2.0)).collect();
+        let mut seen = HashSet::with_capacity(keyword_len);
+        for (i, document) in keyword.documents.into_iter().enumerate() {
+            let keyword_score = 1.0 - i as f32 / keyword_len as f32;
+            let semantic_score = semantic_scores.get(&document.id).copied().unwrap_or(0.0);
+            seen.insert(document.id);
+            scored.push(((1.0 - semantic_ratio) * keyword_score + semantic_ratio * semantic_score, document));
+        }
+        for (id, _) in &similarities {
+            if !seen.contains(id) {
+                scored.push((semantic_ratio * semantic_scores[id], Document::from_highlights(*id, &[])));
+            }
+        }
+
+        scored.sort_by(|(a, da), (b, db)| b.partial_cmp(a).unwrap_or(Ordering::Equal).then(da.id.cmp(&db.id)));
+        let docids = SetBuf::from_dirty(scored.iter().map(|(_, document)| document.id).collect());
+        let documents = scored.into_iter().map(|(_, document)| document).collect();
+
+        let distinct = distinct.as_ref().map(|(distinct, size)| (distinct.as_ref(), *size));
+        let (documents, nb_hits) = paginate_scored_documents(documents, range, distinct);
+
+        // the keyword matches ranked after the window are not blended but still count
+        let missed = keyword.nb_hits.saturating_sub(keyword_len);
+        let mut result = SortResult {
+            documents,
+            nb_hits: nb_hits + missed,
+            exhaustive_nb_hit: keyword.exhaustive_nb_hit && missed == 0,
+            partial: keyword.partial,
+            ..SortResult::default()
+        };
+
+        if let Some(f) = facet_count_docids {
+            result.exhaustive_facets_count = Some(missed == 0);
+            result.facets = Some(facet_count(f, &docids));
+        }
+
+        Ok(result)
+    }
+
     fn placeholder_query(self, reader: &heed::RoTxn<MainT>, range: Range<usize>) -> MResult<SortResult> {
+        if let Some(ref sort) = self.sort {
+            return self.sorted_placeholder_query(reader, sort, range);
+        }
+
+        let ranking_rules = self.index.main.ranking_rules(reader)?.unwrap_or_default();
+
         match self.facets_docids(reader)? {
             Some(docids) => {
                 // We sort the docids from facets according to the criteria set by the user
@@ -169,10 +372,10 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
                 let mut sort_result = match self.index.main.ranked_map(reader)? {
                     Some(ranked_map) => {
                         placeholder_document_sort(&mut sorted_docids, self.index, reader, &ranked_map)?;
-                        self.sort_result_from_docids(&sorted_docids, range)
+                        self.sort_result_from_docids(reader, &sorted_docids, &ranking_rules, range)?
                     },
                     // if we can't perform a sort, we return documents unordered
-                    None => self.sort_result_from_docids(&docids, range),
+                    None => self.sort_result_from_docids(reader, &docids, &ranking_rules, range)?,
                 };
 
                 if let Some(f) = self.facet_count_docids(reader)? {
@@ -186,7 +389,7 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
                 match self.index.main.sorted_document_ids_cache(reader)? {
                     // build result from cached document ids
                     Some(docids) => {
-                        let mut sort_result = self.sort_result_from_docids(&docids, range);
+                        let mut sort_result = self.sort_result_from_docids(reader, &docids, &ranking_rules, range)?;
 
                         if let Some(f) = self.facet_count_docids(reader)? {
                             sort_result.exhaustive_facets_count = Some(true);
@@ -204,6 +407,37 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
         }
     }
 
+    /// The cached documents ids are sorted according to the index ranking rules, a query
+    /// specific sort must therefore sort the candidates on its own.
+    fn sorted_placeholder_query(
+        &self,
+        reader: &heed::RoTxn<MainT>,
+        sort: &[RankingRule],
+        range: Range<usize>,
+    ) -> MResult<SortResult> {
+        let docids = match self.facets_docids(reader)? {
+            Some(docids) => docids,
+            None => match self.index.main.sorted_document_ids_cache(reader)? {
+                Some(docids) => SetBuf::from_dirty(Vec::from(docids)),
+                None => return Ok(SortResult::default()),
+            },
+        };
+
+        let mut sorted_docids = docids.clone().into_vec();
+        if let (Some(schema), Some(ranked_map)) = (self.index.main.schema(reader)?, self.index.main.ranked_map(reader)?) {
+            custom_rules_document_sort(&mut sorted_docids, &schema, &ranked_map, sort);
+        }
+
+        let mut sort_result = self.sort_result_from_docids(reader, &sorted_docids, sort, range)?;
+
+        if let Some(f) = self.facet_count_docids(reader)? {
+            sort_result.exhaustive_facets_count = Some(true);
+            sort_result.facets = Some(facet_count(f, &docids));
+        }
+
+        Ok(sort_result)
+    }
+
     fn facet_count_docids<'a>(&self, reader: &'a MainReader) -> MResult<Option<HashMap<String, HashMap<String, (&'a str, Cow<'a, Set<DocumentId>>)>>>> {
         match self.facets {
             Some(ref field_ids) => {
@@ -223,65 +457,208 @@ impl<'c, 'f, 'd, 'i> QueryBuilder<'c, 'f, 'd, 'i> {
         }
     }
 
-    fn sort_result_from_docids(&self, docids: &[DocumentId], range: Range<usize>) -> SortResult {
+    /// builds the requested page of documents ids sorted according to the custom rules
+    /// of the given ranking rules, the page starts after the cursor if one is set
+    fn sort_result_from_docids(
+        &self,
+        reader: &heed::RoTxn<MainT>,
+        docids: &[DocumentId],
+        ranking_rules: &[RankingRule],
+        range: Range<usize>,
+    ) -> MResult<SortResult> {
         let mut sort_result = SortResult::default();
-        let mut filtered_count = 0;
-        let mut result = match self.filter {
-            Some(ref filter) => docids
-                .iter()
-                .filter(|item| {
-                    let accepted = (filter)(**item);
-                    if !accepted {
-                        filtered_count += 1;
-                    }
-                    accepted
-                })
-                .skip(range.start)
-                .take(range.end - range.start)
-                .map(|&id| Document::from_highlights(id, &[]))
-                .collect::<Vec<_>>(),
-            None => docids
-                .iter()
-                .skip(range.start)
-                .take(range.end - range.start)
-                .map(|&id| Document::from_highlights(id, &[]))
-                .collect::<Vec<_>>(),
+        let schema = match self.index.main.schema(reader)? {
+            Some(schema) => schema,
+            None => return Ok(sort_result),
         };
+        let ranked_map = self.index.main.ranked_map(reader)?.unwrap_or_default();
 
-        // distinct is set, remove duplicates with disctinct function
-        if let Some((distinct, distinct_size)) = &self.distinct {
-            let mut distinct_map = DistinctMap::new(*distinct_size);
-            let mut distinct_map = BufferedDistinctMap::new(&mut distinct_map);
-            result.retain(|doc| {
-                let id = doc.id;
-                let key = (distinct)(id);
-                let distinct_accepted = match key {
+        let start = match self.cursor {
+            Some(ref cursor) => custom_rules_cursor_position(docids, &schema, &ranked_map, ranking_rules, cursor),
+            None => 0,
+        };
+
+        // the filter and the distinct rule are applied before the page is cut,
+        // the page is full of accepted documents and the cursor points to the last one
+        let mut distinct_map = self.distinct.as_ref().map(|(_, size)| DistinctMap::new(*size));
+        let mut distinct_map = distinct_map.as_mut().map(BufferedDistinctMap::new);
+        let mut filtered_count = 0;
+        let mut accepted_count = 0;
+        let mut result = Vec::with_capacity(range.len());
+
+        for &id in &docids[start..] {
+            if result.len() == range.len() {
+                break;
+            }
+
+            if !self.filter.as_ref().map_or(true, |filter| (filter)(id)) {
+                filtered_count += 1;
+                continue;
+            }
+
+            let distinct_accepted = match (&self.distinct, &mut distinct_map) {
+                (Some((distinct, _)), Some(distinct_map)) => match (distinct)(id) {
                     Some(key) => distinct_map.register(key),
                     None => distinct_map.register_without_key(),
-                };
-                if !distinct_accepted {
-                    filtered_count += 1;
-                }
-                distinct_accepted
-            });
+                },
+                _ => true,
+            };
+
+            if !distinct_accepted {
+                filtered_count += 1;
+                continue;
+            }
+
+            accepted_count += 1;
+            if accepted_count > range.start {
+                result.push(Document::from_highlights(id, &[]));
+            }
+        }
+
+        if result.len() == range.len() {
+            sort_result.cursor = result
+                .last()
+                .map(|document| custom_rules_cursor(document.id, &schema, &ranked_map, ranking_rules));
         }
 
         sort_result.documents = result;
-        sort_result.nb_hits = docids.len() - filtered_count;
-        sort_result
+
+        match self.nb_hits {
+            NbHits::Estimated => sort_result.nb_hits = docids.len() - filtered_count,
+            nb_hits => {
+                let filter = self.filter.as_deref();
+                let distinct = self.distinct.as_ref().map(|(distinct, size)| (distinct.as_ref(), *size));
+                let cap = nb_hits.cap();
+                let count = count_documents(docids, filter, distinct, cap);
+                sort_result.nb_hits = count;
+                sort_result.exhaustive_nb_hit = cap.map_or(true, |cap| count < cap);
+            }
+        }
+
+        Ok(sort_result)
     }
 
     pub fn query(
-        self,
+        mut self,
+        reader: &heed::RoTxn<MainT>,
+        query: Option<&str>,
+        range: Range<usize>,
+    ) -> MResult<SortResult> {
+        let mut pinned_documents = std::mem::take(&mut self.pinned_documents);
+        if !pinned_documents.is_empty() {
+            // the pinned documents must match the filters of the search like the other ones
+            let facets_docids = self.facets_docids(reader)?;
+            let filter = self.filter.as_deref();
+            pinned_documents.retain(|(_, id)| {
+                facets_docids.as_ref().map_or(true, |ids| ids.binary_search(id).is_ok())
+                    && filter.map_or(true, |filter| filter(*id))
+            });
+        }
+
+        if pinned_documents.is_empty() {
+            return self.ranked_query(reader, query, range);
+        }
+
+        // the pinned documents are placed by position, the pages can't start after a document
+        if self.cursor.is_some() {
+            return Err(Error::InvalidCursor);
+        }
+
+        // the pinned documents are inserted after the ranking, they must not be ranked as well
+        let pinned_ids: HashSet<_> = pinned_documents.iter().map(|(_, id)| *id).collect();
+        let filter = self.filter.take();
+        self.filter = Some(Box::new(move |id| {
+            !pinned_ids.contains(&id) && filter.as_ref().map_or(true, |filter| filter(id))
+        }));
+
+        // the ranked documents before the page are needed to know where the pinned ones go
+        let mut result = self.ranked_query(reader, query, 0..range.end)?;
+
+        pinned_documents.sort_by_key(|(position, _)| *position);
+        for (position, id) in &pinned_documents {
+            let position = cmp::min(*position, result.documents.len());
+            result.documents.insert(position, Document::from_highlights(*id, &[]));
+        }
+
+        result.documents = result.documents.into_iter().skip(range.start).take(range.len()).collect();
+        result.nb_hits += pinned_documents.len();
+        result.cursor = None;
+
+        Ok(result)
+    }
+
+    fn ranked_query(
+        mut self,
         reader: &heed::RoTxn<MainT>,
         query: Option<&str>,
         range: Range<usize>,
     ) -> MResult<SortResult> {
+        if let Some(vector) = self.vector.take() {
+            return match query {
+                Some(query) if self.semantic_ratio <= 0.0 => self.standard_query(reader, query, range),
+                Some(query) if self.semantic_ratio < 1.0 => self.hybrid_query(reader, query, &vector, range),
+                _ => self.vector_query(reader, &vector, range),
+            };
+        }
+
         match query {
             Some(query) => self.standard_query(reader, query, range),
-            None => self.placeholder_query(reader, range),
+            None => {
+                // without criteria, a placeholder query is only ranked by the custom rules
+                let index = self.index;
+                let ranking_rules = match (self.ranking_infos, &self.sort) {
+                    (false, _) => None,
+                    (true, Some(sort)) => Some(sort.clone()),
+                    (true, None) => Some(index.main.ranking_rules(reader)?.unwrap_or_default()),
+                };
+
+                let mut result = self.placeholder_query(reader, range)?;
+
+                if let Some(ranking_rules) = ranking_rules {
+                    if let (Some(schema), Some(ranked_map)) = (index.main.schema(reader)?, index.main.ranked_map(reader)?) {
+                        for document in &mut result.documents {
+                            document.ranking_infos = custom_rules_ranking_infos(document.id, &schema, &ranked_map, &ranking_rules);
+                        }
+                    }
+                }
+
+                Ok(result)
+            }
+        }
+    }
+}
+
+/// keeps the requested page of documents already sorted by score, returns it with the number
+/// of documents accepted by the distinct rule
+fn paginate_scored_documents(
+    documents: Vec<Document>,
+    range: Range<usize>,
+    distinct: Option<(&dyn Fn(DocumentId) -> Option<u64>, usize)>,
+) -> (Vec<Document>, usize)
+{
+    let mut distinct_map = distinct.map(|(_, size)| DistinctMap::new(size));
+    let mut distinct_map = distinct_map.as_mut().map(BufferedDistinctMap::new);
+    let mut page = Vec::with_capacity(range.len());
+    let mut count = 0;
+
+    for document in documents {
+        let accepted = match (distinct, &mut distinct_map) {
+            (Some((distinct, _)), Some(distinct_map)) => match (distinct)(document.id) {
+                Some(key) => distinct_map.register(key),
+                None => distinct_map.register_without_key(),
+            },
+            _ => true,
+        };
+
+        if accepted {
+            if range.contains(&count) {
+                page.push(document);
+            }
+            count += 1;
         }
     }
+
+    (page, count)
 }
 
 #[cfg(test)]
@@ -301,6 +678,8 @@ mod tests {
     use crate::store::Index;
     use crate::DocIndex;
     use crate::Document;
+    use crate::Highlight;
+    use crate::Number;
     use meilisearch_schema::Schema;
 
     fn is_cjk(c: char) -> bool {
@@ -1440,4 +1819,401 @@ mod tests {
         });
         assert_matches!(iter.next(), None);
     }
+
+    #[test]
+    fn quoted_phrase_query() {
+        let store = TempDatabase::from_iter(vec![
+            ("new", &[doc_index(0, 0)][..]),
+            ("york", &[doc_index(0, 1)][..]),
+            ("city", &[doc_index(0, 2)][..]),
+            ("york", &[doc_index(1, 0)][..]),
+            ("new", &[doc_index(1, 1)][..]),
+            ("new", &[doc_index(2, 0)][..]),
+            ("big", &[doc_index(2, 1)][..]),
+            ("york", &[doc_index(2, 2)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, Some("\"new york\" city"), 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
+            let mut iter = matches.into_iter();
+            assert_matches!(iter.next(), Some(SimpleMatch { word_index: 0, distance: 0, .. })); // new
+            assert_matches!(iter.next(), Some(SimpleMatch { word_index: 1, distance: 0, .. })); // york
+            assert_matches!(iter.next(), Some(SimpleMatch { word_index: 2, distance: 0, .. })); // city
+            assert_matches!(iter.next(), None);
+        });
+        assert_matches!(iter.next(), None);
+
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, Some("\"new york"), 0..20).unwrap();
+        assert_eq!(documents.len(), 3);
+    }
+
+    #[test]
+    fn quoted_phrase_query_highlights() {
+        let new = DocIndex { document_id: DocumentId(0), attribute: 0, word_index: 0, char_index: 0, char_length: 3 };
+        let york = DocIndex { document_id: DocumentId(0), attribute: 0, word_index: 1, char_index: 4, char_length: 4 };
+        let store = TempDatabase::from_iter(vec![
+            ("new", &[new][..]),
+            ("york", &[york][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let builder = store.query_builder();
+        let SortResult { documents, .. } = builder.query(&reader, Some("\"new york\""), 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), highlights, .. }) => {
+            let mut iter = highlights.into_iter();
+            assert_matches!(iter.next(), Some(Highlight { char_index: 0, char_length: 8, .. }));
+            assert_matches!(iter.next(), None);
+        });
+        assert_matches!(iter.next(), None);
+    }
+
+    #[test]
+    fn excluded_words() {
+        let store = TempDatabase::from_iter(vec![
+            ("laptop", &[doc_index(0, 0)][..]),
+            ("laptop", &[doc_index(1, 0)][..]),
+            ("refurbished", &[doc_index(1, 1)][..]),
+            ("laptop", &[doc_index(2, 0)][..]),
+            ("refurbishee", &[doc_index(2, 1)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let builder = store.query_builder();
+        let SortResult { documents, nb_hits, .. } = builder.query(&reader, Some("laptop -refurbished"), 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), matches, .. }) => {
+            let mut iter = matches.into_iter();
+            assert_matches!(iter.next(), Some(SimpleMatch { query_index: 0, word_index: 0, distance: 0, .. })); // laptop
+            assert_matches!(iter.next(), None);
+        });
+        // the exclusion is not typo tolerant
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(2), matches, .. }) => {
+            let mut iter = matches.into_iter();
+            assert_matches!(iter.next(), Some(SimpleMatch { query_index: 0, word_index: 0, distance: 0, .. })); // laptop
+            assert_matches!(iter.next(), None);
+        });
+        assert_matches!(iter.next(), None);
+        assert_eq!(nb_hits, 2);
+    }
+
+    #[test]
+    fn ranking_infos() {
+        let store = TempDatabase::from_iter(vec![
+            ("new", &[doc_index(0, 0)][..]),
+            ("york", &[doc_index(0, 1)][..]),
+            ("new", &[doc_index(1, 0)][..]),
+            ("york", &[doc_index(1, 3)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let mut builder = store.query_builder();
+        builder.set_ranking_infos(true);
+        let SortResult { documents, .. } = builder.query(&reader, Some("new york"), 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), ranking_infos, .. }) => {
+            let names: Vec<_> = ranking_infos.iter().map(|(name, _)| name.as_str()).collect();
+            assert_eq!(names, ["typo", "words", "proximity", "attribute", "words position", "exactness"]);
+            assert_eq!(ranking_infos[0].1, Number::Unsigned(0));
+            assert_eq!(ranking_infos[1].1, Number::Unsigned(2));
+            assert_eq!(ranking_infos[2].1, Number::Unsigned(1));
+        });
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(1), ranking_infos, .. }) => {
+            assert_eq!(ranking_infos[2].1, Number::Unsigned(3));
+        });
+        assert_matches!(iter.next(), None);
+    }
+
+    #[test]
+    fn searchable_attributes() {
+        let title = DocIndex { document_id: DocumentId(0), attribute: 0, word_index: 0, char_index: 0, char_length: 6 };
+        let description = DocIndex { document_id: DocumentId(1), attribute: 1, word_index: 0, char_index: 0, char_length: 6 };
+        let store = TempDatabase::from_iter(vec![
+            ("iphone", &[title][..]),
+            ("iphone", &[description][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let mut builder = store.query_builder();
+        builder.add_searchable_attribute(1);
+        let SortResult { documents, nb_hits, .. } = builder.query(&reader, Some("iphone"), 0..20).unwrap();
+        let mut iter = documents.into_iter();
+
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(1), highlights, .. }) => {
+            let mut iter = highlights.into_iter();
+            // only the match of the searchable attribute is highlighted
+            assert_matches!(iter.next(), Some(Highlight { .. }));
+            assert_matches!(iter.next(), None);
+        });
+        assert_matches!(iter.next(), None);
+        assert_eq!(nb_hits, 1);
+    }
+
+    #[test]
+    fn fetch_timeout() {
+        let store = TempDatabase::from_iter(vec![
+            ("iphone", &[doc_index(0, 0)][..]),
+            ("iphone", &[doc_index(1, 0)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let builder = store.query_builder();
+        let SortResult { partial, exhaustive_nb_hit, .. } = builder.query(&reader, Some("iphone"), 0..20).unwrap();
+        assert!(!partial);
+        assert!(exhaustive_nb_hit);
+
+        // the time budget is exhausted right away, no criterion is applied
+        // but the documents are still returned and counted
+        let mut builder = store.query_builder();
+        builder.with_fetch_timeout(Duration::from_secs(0));
+        let SortResult { documents, partial, exhaustive_nb_hit, .. } = builder.query(&reader, Some("iphone"), 0..20).unwrap();
+        assert!(partial);
+        assert!(exhaustive_nb_hit);
+        assert_eq!(documents.len(), 2);
+
+        // the documents of a search that timed out are not all sorted, no cursor is returned
+        let mut builder = store.query_builder();
+        builder.with_fetch_timeout(Duration::from_secs(0));
+        let SortResult { documents, partial, cursor, .. } = builder.query(&reader, Some("iphone"), 0..1).unwrap();
+        assert!(partial);
+        assert_eq!(documents.len(), 1);
+        assert_eq!(cursor, None);
+    }
+
+    #[test]
+    fn cursor() {
+        let store = TempDatabase::from_iter(vec![
+            ("iphone", &[doc_index(0, 0)][..]),
+            ("iphone", &[doc_index(1, 0)][..]),
+            ("iphone", &[doc_index(2, 0)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let builder = store.query_builder();
+        let SortResult { documents, cursor, .. } = builder.query(&reader, Some("iphone"), 0..2).unwrap();
+        let mut iter = documents.into_iter();
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(0), .. }));
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(1), .. }));
+        assert_matches!(iter.next(), None);
+        // the cursor holds the keys of the seven criteria
+        assert_matches!(&cursor, Some(cursor) if cursor.document_id == DocumentId(1) && cursor.key.len() == 7);
+
+        // the next page starts right after the document the cursor points to
+        let mut builder = store.query_builder();
+        builder.set_cursor(cursor.clone());
+        let SortResult { documents, cursor: next_cursor, nb_hits, .. } = builder.query(&reader, Some("iphone"), 0..2).unwrap();
+        let mut iter = documents.into_iter();
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(2), .. }));
+        assert_matches!(iter.next(), None);
+        assert_eq!(next_cursor, None);
+        assert_eq!(nb_hits, 3);
+
+        // the document the cursor points to doesn't need to exist anymore
+        let other_store = TempDatabase::from_iter(vec![
+            ("iphone", &[doc_index(0, 0)][..]),
+            ("iphone", &[doc_index(2, 0)][..]),
+        ]);
+        let other_reader = other_store.database.main_read_txn().unwrap();
+        let mut builder = other_store.query_builder();
+        builder.set_cursor(cursor);
+        let SortResult { documents, .. } = builder.query(&other_reader, Some("iphone"), 0..2).unwrap();
+        let mut iter = documents.into_iter();
+        assert_matches!(iter.next(), Some(Document { id: DocumentId(2), .. }));
+        assert_matches!(iter.next(), None);
+
+        // the cursor must have been built with the same criteria
+        let mut builder = store.query_builder();
+        builder.set_cursor(Some(Cursor::new(Vec::new(), DocumentId(1))));
+        let result = builder.query(&reader, Some("iphone"), 0..2);
+        assert_matches!(result, Err(crate::Error::InvalidCursor));
+    }
+
+    #[test]
+    fn exhaustive_nb_hits() {
+        let store = TempDatabase::from_iter(vec![
+            ("iphone", &[doc_index(0, 0)][..]),
+            ("iphone", &[doc_index(1, 0)][..]),
+            ("iphone", &[doc_index(2, 0)][..]),
+            ("iphone", &[doc_index(3, 0)][..]),
+            ("iphone", &[doc_index(4, 0)][..]),
+            ("iphone", &[doc_index(5, 0)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let mut builder = store.query_builder();
+        builder.with_filter(|id| id.0 % 2 == 0);
+        builder.set_nb_hits(NbHits::Exhaustive);
+        let SortResult { documents, nb_hits, exhaustive_nb_hit, .. } = builder.query(&reader, Some("iphone"), 0..1).unwrap();
+        assert_eq!(documents.len(), 1);
+        assert_eq!(nb_hits, 3);
+        assert!(exhaustive_nb_hit);
+
+        // the count is exact below the cap
+        let mut builder = store.query_builder();
+        builder.with_filter(|id| id.0 % 2 == 0);
+        builder.set_nb_hits(NbHits::Capped(10));
+        let SortResult { nb_hits, exhaustive_nb_hit, .. } = builder.query(&reader, Some("iphone"), 0..1).unwrap();
+        assert_eq!(nb_hits, 3);
+        assert!(exhaustive_nb_hit);
+
+        let mut builder = store.query_builder();
+        builder.with_filter(|id| id.0 % 2 == 0);
+        builder.set_nb_hits(NbHits::Capped(2));
+        let SortResult { nb_hits, exhaustive_nb_hit, .. } = builder.query(&reader, Some("iphone"), 0..1).unwrap();
+        assert_eq!(nb_hits, 2);
+        assert!(!exhaustive_nb_hit);
+
+        // the distinct rule keeps one document per key
+        let mut builder = store.query_builder();
+        builder.with_distinct(1, |id| Some(u64::from(id.0 % 2)));
+        builder.set_nb_hits(NbHits::Exhaustive);
+        let SortResult { nb_hits, exhaustive_nb_hit, .. } = builder.query(&reader, Some("iphone"), 0..1).unwrap();
+        assert_eq!(nb_hits, 2);
+        assert!(exhaustive_nb_hit);
+    }
+
+    #[test]
+    fn matching_strategy() {
+        let store = TempDatabase::from_iter(vec![
+            ("hello", &[doc_index(0, 0)][..]),
+            ("world", &[doc_index(0, 1)][..]),
+            ("hello", &[doc_index(1, 0)][..]),
+            ("world", &[doc_index(2, 0)][..]),
+            ("hello", &[doc_index(3, 0)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let ids = |strategy| {
+            let mut builder = store.query_builder();
+            builder.set_matching_strategy(strategy);
+            let SortResult { documents, .. } = builder.query(&reader, Some("hello world"), 0..20).unwrap();
+            documents.into_iter().map(|d| d.id.0).collect::<Vec<_>>()
+        };
+
+        // every word must match
+        assert_eq!(ids(MatchingStrategy::All), vec![0]);
+        // world is dropped first, being the last word of the query
+        assert_eq!(ids(MatchingStrategy::Last), vec![0, 1, 3]);
+        // hello is dropped first, being the most frequent word
+        assert_eq!(ids(MatchingStrategy::Frequency), vec![0, 2]);
+    }
+
+    #[test]
+    fn exact_words() {
+        let store = TempDatabase::from_iter(vec![
+            ("hello", &[doc_index(0, 0)][..]),
+            ("hallo", &[doc_index(1, 0)][..]),
+            ("helloworld", &[doc_index(2, 0)][..]),
+            ("world", &[doc_index(3, 0)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let ids = |exact_words, query| {
+            let mut builder = store.query_builder();
+            builder.set_exact_words(exact_words);
+            let SortResult { documents, .. } = builder.query(&reader, Some(query), 0..20).unwrap();
+            let mut ids: Vec<_> = documents.into_iter().map(|d| d.id.0).collect();
+            ids.sort_unstable();
+            ids
+        };
+
+        // the typos and the prefixes match
+        assert_eq!(ids(false, "hello"), vec![0, 1, 2]);
+        assert_eq!(ids(true, "hello"), vec![0]);
+
+        // the words are concatenated
+        assert_eq!(ids(false, "hello world"), vec![2]);
+        assert_eq!(ids(true, "hello world"), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn pinned_documents() {
+        let store = TempDatabase::from_iter(vec![
+            ("iphone", &[doc_index(0, 0)][..]),
+            ("iphone", &[doc_index(1, 0)][..]),
+            ("iphone", &[doc_index(2, 0)][..]),
+            ("samsung", &[doc_index(3, 0)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        let ids = |pinned, filtered: Option<u32>| {
+            let mut builder = store.query_builder();
+            builder.set_pinned_documents(pinned);
+            if let Some(filtered) = filtered {
+                builder.with_filter(move |id| id.0 != filtered);
+            }
+            let SortResult { documents, nb_hits, .. } = builder.query(&reader, Some("iphone"), 0..20).unwrap();
+            (documents.into_iter().map(|d| d.id.0).collect::<Vec<_>>(), nb_hits)
+        };
+
+        assert_eq!(ids(vec![(0, DocumentId(3))], None), (vec![3, 0, 1, 2], 4));
+        // a ranked document that is pinned only appears once
+        assert_eq!(ids(vec![(0, DocumentId(2))], None), (vec![2, 0, 1], 3));
+        // a pinned document must match the filters
+        assert_eq!(ids(vec![(0, DocumentId(3))], Some(3)), (vec![0, 1, 2], 3));
+
+        // the pinned documents can't be paginated with a cursor
+        let builder = store.query_builder();
+        let SortResult { cursor, .. } = builder.query(&reader, Some("iphone"), 0..1).unwrap();
+        let mut builder = store.query_builder();
+        builder.set_cursor(cursor);
+        builder.set_pinned_documents(vec![(0, DocumentId(3))]);
+        let result = builder.query(&reader, Some("iphone"), 0..1);
+        assert_matches!(result, Err(crate::Error::InvalidCursor));
+    }
+
+    #[test]
+    fn correct_query() {
+        let store = TempDatabase::from_iter(vec![
+            ("hello", &[doc_index(0, 0)][..]),
+            ("hello", &[doc_index(1, 0)][..]),
+            ("help", &[doc_index(2, 0)][..]),
+            ("world", &[doc_index(0, 1)][..]),
+        ]);
+
+        let db = &store.database;
+        let reader = db.main_read_txn().unwrap();
+
+        // helo is as close to hello as to help, hello is more frequent
+        let corrected = store.index.correct_query(&reader, "helo wrld").unwrap();
+        assert_eq!(corrected.as_deref(), Some("hello world"));
+
+        // the last word is a prefix of world
+        let corrected = store.index.correct_query(&reader, "hello wor").unwrap();
+        assert_eq!(corrected, None);
+
+        // the excluded words are kept, the phrases keep their quotes
+        let corrected = store.index.correct_query(&reader, "-helo \"wrld\"").unwrap();
+        assert_eq!(corrected.as_deref(), Some("-helo \"world\""));
+    }
 }
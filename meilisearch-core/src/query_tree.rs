@@ -1,5 +1,5 @@
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::ops::Range;
 use std::time::Instant;
@@ -12,6 +12,7 @@ use meilisearch_tokenizer::analyzer::{Analyzer, AnalyzerConfig};
 use sdset::{Set, SetBuf, SetOperation};
 
 use crate::database::MainT;
+use crate::settings::TypoTolerance;
 use crate::{store, DocumentId, DocIndex, MResult, FstSetCow};
 use crate::automaton::{build_dfa, build_prefix_dfa, build_exact_dfa};
 use crate::QueryWordsMapper;
@@ -56,6 +57,11 @@ impl Operation {
         let kind = QueryKind::Phrase(vec![left.to_owned(), right.to_owned()]);
         Operation::Query(Query { id, prefix, exact: true, kind })
     }
+
+    fn phrase(id: QueryId, words: &[String]) -> Operation {
+        let kind = QueryKind::Phrase(words.to_vec());
+        Operation::Query(Query { id, prefix: false, exact: true, kind })
+    }
 }
 
 pub type QueryId = usize;
@@ -118,6 +124,30 @@ pub struct Context<'a> {
     pub synonyms: store::Synonyms,
     pub postings_lists: store::PostingsLists,
     pub prefix_postings_lists: store::PrefixPostingsListsCache,
+    pub typo_tolerance: TypoTolerance,
+    /// The indexed positions of the attributes where typos are not allowed.
+    pub typo_disabled_attributes: HashSet<u16>,
+    pub matching_strategy: MatchingStrategy,
+    /// The words of the query are searched as they are written, without typos, prefixes,
+    /// synonyms, splits nor concatenations.
+    pub exact_words: bool,
+}
+
+/// Which words of the query a document can miss and still match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchingStrategy {
+    /// Every word of the query must match.
+    All,
+    /// The words at the end of the query are optional, they are dropped one after the other.
+    Last,
+    /// The words are dropped from the most frequent in the index to the least frequent one.
+    Frequency,
+}
+
+impl Default for MatchingStrategy {
+    fn default() -> MatchingStrategy {
+        MatchingStrategy::All
+    }
 }
 
 fn split_best_frequency<'a>(reader: &heed::RoTxn<MainT>, ctx: &Context, word: &'a str) -> MResult<Option<(&'a str, &'a str)>> {
@@ -185,36 +215,186 @@ fn split_query_string<'a, A: AsRef<[u8]>>(s: &str, stop_words: &'a fst::Set<A>)
         .collect()
 }
 
+/// A part of the query string, either a free word or the words of a quoted phrase,
+/// along with the index of its first word in the query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum QueryToken {
+    Word(usize, String),
+    Phrase(usize, Vec<String>),
+}
+
+impl QueryToken {
+    fn id(&self) -> usize {
+        match self {
+            QueryToken::Word(id, _) => *id,
+            QueryToken::Phrase(id, _) => *id,
+        }
+    }
+}
+
+/// Splits the query string into words, the words between double quotes are kept
+/// together as a phrase. An unterminated quote is ignored.
+///
+/// The words prefixed by a `-` are not part of the query, they are returned
+/// separately as the words the documents must not contain.
+fn split_query_tokens<'a, A: AsRef<[u8]>>(s: &str, stop_words: &'a fst::Set<A>) -> (Vec<QueryToken>, Vec<String>) {
+    let quotes = s.matches('"').count();
+
+    let mut tokens = Vec::new();
+    let mut excluded = Vec::new();
+    let mut next_id = 0;
+
+    for (i, part) in s.split('"').enumerate() {
+        let is_phrase = i % 2 == 1 && i < quotes;
+
+        if is_phrase {
+            let words: Vec<_> = split_query_string(part, stop_words).into_iter().map(|(_, w)| w).collect();
+            if !words.is_empty() {
+                let len = words.len();
+                tokens.push(QueryToken::Phrase(next_id, words));
+                next_id += len;
+            }
+        } else {
+            let mut included = Vec::new();
+            for chunk in part.split_whitespace() {
+                match chunk.strip_prefix('-') {
+                    Some(word) if !word.is_empty() => {
+                        let words = split_query_string(word, stop_words).into_iter().map(|(_, w)| w);
+                        excluded.extend(words);
+                    },
+                    _ => included.push(chunk),
+                }
+            }
+
+            for (_, word) in split_query_string(&included.join(" "), stop_words) {
+                tokens.push(QueryToken::Word(next_id, word));
+                next_id += 1;
+            }
+        }
+    }
+
+    (tokens, excluded)
+}
+
+/// Returns whether the word starting at the given byte of the query is excluded, it is
+/// prefixed by a `-` outside of a phrase, following the rules of `split_query_tokens`.
+pub(crate) fn is_excluded_word(query: &str, byte_start: usize) -> bool {
+    let before = &query[..byte_start];
+    let quotes_before = before.matches('"').count();
+    if quotes_before % 2 == 1 && quotes_before < query.matches('"').count() {
+        return false;
+    }
+
+    let chunk_start = match before.char_indices().rev().find(|(_, c)| c.is_whitespace() || *c == '"') {
+        Some((i, c)) => i + c.len_utf8(),
+        None => 0,
+    };
+    query[chunk_start..].starts_with('-')
+}
+
+/// Returns the documents that contain any of the given words, without typo tolerance.
+pub fn excluded_documents(
+    reader: &heed::RoTxn<MainT>,
+    ctx: &Context,
+    words: &[String],
+) -> MResult<SetBuf<DocumentId>>
+{
+    let mut docids = Vec::new();
+    for word in words {
+        if let Some(postings_list) = ctx.postings_lists.postings_list(reader, word.as_bytes())? {
+            docids.extend_from_slice(&postings_list.docids);
+        }
+    }
+    Ok(SetBuf::from_dirty(docids))
+}
+
+/// Returns the indexes of the tokens in the order they are dropped from the query,
+/// the first token that is kept is not part of it.
+fn dropping_order(
+    reader: &heed::RoTxn<MainT>,
+    ctx: &Context,
+    tokens: &[QueryToken],
+) -> MResult<Option<Vec<usize>>>
+{
+    if tokens.len() < 2 {
+        return Ok(None);
+    }
+
+    let mut order: Vec<_> = (0..tokens.len()).rev().collect();
+    match ctx.matching_strategy {
+        MatchingStrategy::All => return Ok(None),
+        MatchingStrategy::Last => (),
+        MatchingStrategy::Frequency => {
+            let mut frequencies = Vec::with_capacity(tokens.len());
+            for token in tokens {
+                // the phrases are the most specific parts of the query, they are dropped last
+                let frequency = match token {
+                    QueryToken::Word(_, word) => ctx.postings_lists
+                        .postings_list(reader, word.as_bytes())?
+                        .map_or(0, |p| p.docids.len()),
+                    QueryToken::Phrase(..) => 0,
+                };
+                frequencies.push(frequency);
+            }
+            // the sort is stable, between equally frequent words the last one is dropped first
+            order.sort_by_key(|&i| cmp::Reverse(frequencies[i]));
+        }
+    }
+
+    order.pop();
+    Ok(Some(order))
+}
+
 pub fn create_query_tree(
     reader: &heed::RoTxn<MainT>,
     ctx: &Context,
     query: &str,
-) -> MResult<(Operation, HashMap<QueryId, Range<usize>>)>
+) -> MResult<(Operation, HashMap<QueryId, Range<usize>>, Vec<String>)>
 {
     // TODO: use a shared analyzer instance
-    let words = split_query_string(query, &ctx.stop_words);
+    let (tokens, excluded) = split_query_tokens(query, &ctx.stop_words);
 
-    let mut mapper = QueryWordsMapper::new(words.iter().map(|(_, w)| w));
+    let originals = tokens.iter().flat_map(|token| match token {
+        QueryToken::Word(_, word) => vec![word.as_str()],
+        QueryToken::Phrase(_, words) => words.iter().map(String::as_str).collect(),
+    });
+    let mut mapper = QueryWordsMapper::new(originals);
 
     fn create_inner(
         reader: &heed::RoTxn<MainT>,
         ctx: &Context,
         mapper: &mut QueryWordsMapper,
-        words: &[(usize, String)],
+        tokens: &[QueryToken],
+        is_query_end: bool,
     ) -> MResult<Vec<Operation>>
     {
         let mut alts = Vec::new();
 
-        for ngram in 1..=MAX_NGRAM {
-            if let Some(group) = words.get(..ngram) {
+        let max_ngram = if ctx.exact_words { 1 } else { MAX_NGRAM };
+        for ngram in 1..=max_ngram {
+            if let Some(group) = tokens.get(..ngram) {
                 let mut group_ops = Vec::new();
 
-                let tail = &words[ngram..];
-                let is_last = tail.is_empty();
+                let tail = &tokens[ngram..];
+                let is_last = tail.is_empty() && is_query_end;
 
                 let mut group_alts = Vec::new();
                 match group {
-                    [(id, word)] => {
+                    [QueryToken::Phrase(id, words)] => {
+                        let range = (*id)..id+words.len();
+
+                        // the phrase reserves the ids following its own for each of its words
+                        let id = (id + 1) * 100;
+                        mapper.declare(range, id, words);
+
+                        group_alts.push(Operation::phrase(id, words));
+                    },
+                    // a phrase is never merged with its neighbours into a n-gram
+                    group if group.iter().any(|t| matches!(t, QueryToken::Phrase(..))) => continue,
+                    [QueryToken::Word(id, word)] if ctx.exact_words => {
+                        group_alts.push(Operation::non_tolerant(*id, false, word));
+                    },
+                    [QueryToken::Word(id, word)] => {
                         let mut idgen = ((id + 1) * 100)..;
                         let range = (*id)..id+1;
 
@@ -249,11 +429,14 @@ pub fn create_query_tree(
                         group_alts.extend(synonyms.chain(phrase));
                     },
                     words => {
-                        let id = words[0].0;
+                        let id = words[0].id();
                         let mut idgen = ((id + 1) * 100_usize.pow(ngram as u32))..;
                         let range = id..id+ngram;
 
-                        let words: Vec<_> = words.iter().map(|(_, s)| s.as_str()).collect();
+                        let words: Vec<_> = words.iter().filter_map(|t| match t {
+                            QueryToken::Word(_, s) => Some(s.as_str()),
+                            QueryToken::Phrase(..) => None,
+                        }).collect();
 
                         for synonym in fetch_synonyms(reader, ctx, &words)? {
                             let exact = synonym.len() == 1;
@@ -279,7 +462,7 @@ pub fn create_query_tree(
                 group_ops.push(create_operation(group_alts, Operation::Or));
 
                 if !tail.is_empty() {
-                    let tail_ops = create_inner(reader, ctx, mapper, tail)?;
+                    let tail_ops = create_inner(reader, ctx, mapper, tail, is_query_end)?;
                     group_ops.push(create_operation(tail_ops, Operation::Or));
                 }
 
@@ -290,11 +473,41 @@ pub fn create_query_tree(
         Ok(alts)
     }
 
-    let alternatives = create_inner(reader, ctx, &mut mapper, &words)?;
+    let alternatives = match dropping_order(reader, ctx, &tokens)? {
+        None => create_inner(reader, ctx, &mut mapper, &tokens, true)?,
+        Some(order) => {
+            // the documents can match fewer and fewer words, the words criterion
+            // ranks the ones that match the most words first
+            let mut alternatives = Vec::with_capacity(tokens.len());
+            let mut kept = vec![true; tokens.len()];
+            for dropped in once(None).chain(order.into_iter().map(Some)) {
+                if let Some(index) = dropped {
+                    kept[index] = false;
+                }
+
+                // the n-grams are only built from the words that follow each other
+                let mut runs_ops = Vec::new();
+                let mut start = 0;
+                for run in kept.split(|&kept| !kept) {
+                    if !run.is_empty() {
+                        let end = start + run.len();
+                        let is_query_end = end == tokens.len();
+                        let ops = create_inner(reader, ctx, &mut mapper, &tokens[start..end], is_query_end)?;
+                        runs_ops.push(create_operation(ops, Operation::Or));
+                    }
+                    start += run.len() + 1;
+                }
+
+                alternatives.push(create_operation(runs_ops, Operation::And));
+            }
+            alternatives
+        }
+    };
+
     let operation = Operation::Or(alternatives);
     let mapping = mapper.mapping();
 
-    Ok((operation, mapping))
+    Ok((operation, mapping, excluded))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -440,7 +653,8 @@ pub fn traverse_query_tree<'o, 'txn>(
                     Cow::Owned(docids)
 
                 } else {
-                    let dfa = if *prefix { build_prefix_dfa(word) } else { build_dfa(word) };
+                    let typos = ctx.typo_tolerance.max_typos(word);
+                    let dfa = if *prefix { build_prefix_dfa(word, typos) } else { build_dfa(word, typos) };
 
                     let byte = word.as_bytes()[0];
                     let mut stream = if byte == u8::max_value() {
@@ -455,9 +669,25 @@ pub fn traverse_query_tree<'o, 'txn>(
                         if let Some(result) = ctx.postings_lists.postings_list(reader, input)? {
                             let distance = dfa.eval(input).to_u8();
                             let is_exact = *exact && distance == 0 && input.len() == word.len();
-                            results.push(result.docids);
                             let key = PostingsKey { query, input: input.to_owned(), distance, is_exact };
-                            postings.insert(key, result.matches);
+
+                            if distance != 0 && !ctx.typo_disabled_attributes.is_empty() {
+                                // matches with typos are only valid outside of the attributes
+                                // where the typos are disabled
+                                let matches: Vec<_> = result.matches
+                                    .iter()
+                                    .filter(|m| !ctx.typo_disabled_attributes.contains(&m.attribute))
+                                    .cloned()
+                                    .collect();
+                                let mut docids: Vec<_> = matches.iter().map(|m| m.document_id).collect();
+                                docids.dedup();
+
+                                results.push(Cow::Owned(SetBuf::new_unchecked(docids)));
+                                postings.insert(key, Cow::Owned(SetBuf::new_unchecked(matches)));
+                            } else {
+                                results.push(result.docids);
+                                postings.insert(key, result.matches);
+                            }
                         }
                     }
                     debug!("{:3$}docids retrieval ({:?}) took {:.02?}", "", results.len(), before.elapsed(), depth * 2);
@@ -520,36 +750,46 @@ pub fn traverse_query_tree<'o, 'txn>(
             },
             QueryKind::Phrase(words) => {
                 // TODO support prefix and non-prefix exact DFA
-                if let [first, second] = words.as_slice() {
-                    let first = ctx.postings_lists.postings_list(reader, first.as_bytes())?.unwrap_or_default();
-                    let second = ctx.postings_lists.postings_list(reader, second.as_bytes())?.unwrap_or_default();
+                let mut postings_lists = Vec::with_capacity(words.len());
+                for word in words {
+                    let postings_list = ctx.postings_lists.postings_list(reader, word.as_bytes())?;
+                    postings_lists.push(postings_list.unwrap_or_default());
+                }
+
+                // We follow the matches of the first word and only keep the ones that
+                // are directly followed by the next words of the phrase in the same attribute.
+                let mut chains: Vec<Vec<DocIndex>> = match postings_lists.first() {
+                    Some(first) => first.matches.iter().map(|m| vec![*m]).collect(),
+                    None => Vec::new(),
+                };
 
-                    let iter = merge_join_by(first.matches.as_slice(), second.matches.as_slice(), |a, b| {
-                        let x = (a.document_id, a.attribute, (a.word_index as u32) + 1);
+                for (i, postings_list) in postings_lists.iter().enumerate().skip(1) {
+                    let iter = merge_join_by(chains, postings_list.matches.as_slice(), |chain, b| {
+                        let a = chain[0];
+                        let x = (a.document_id, a.attribute, (a.word_index as u32) + i as u32);
                         let y = (b.document_id, b.attribute, b.word_index as u32);
                         x.cmp(&y)
                     });
 
-                    let matches: Vec<_> = iter
+                    chains = iter
                         .filter_map(EitherOrBoth::both)
-                        .flat_map(|(a, b)| once(*a).chain(Some(*b)))
+                        .map(|(mut chain, b)| { chain.push(*b); chain })
                         .collect();
+                }
 
-                    let before = Instant::now();
-                    let mut docids: Vec<_> = matches.iter().map(|m| m.document_id).collect();
-                    docids.dedup();
-                    let docids = SetBuf::new(docids).unwrap();
-                    debug!("{:2$}docids construction took {:.02?}", "", before.elapsed(), depth * 2);
+                let matches: Vec<_> = chains.into_iter().flatten().collect();
 
-                    let matches = Cow::Owned(SetBuf::from_dirty(matches));
-                    let key = PostingsKey { query, input: vec![], distance: 0, is_exact: true };
-                    postings.insert(key, matches);
+                let before = Instant::now();
+                let mut docids: Vec<_> = matches.iter().map(|m| m.document_id).collect();
+                docids.dedup();
+                let docids = SetBuf::new(docids).unwrap();
+                debug!("{:2$}docids construction took {:.02?}", "", before.elapsed(), depth * 2);
 
-                    Cow::Owned(docids)
-                } else {
-                    debug!("{:2$}{:?} skipped", "", words, depth * 2);
-                    Cow::default()
-                }
+                let matches = Cow::Owned(SetBuf::from_dirty(matches));
+                let key = PostingsKey { query, input: vec![], distance: 0, is_exact: true };
+                postings.insert(key, matches);
+
+                Cow::Owned(docids)
             },
         };
 
@@ -2,40 +2,75 @@ use std::io::{Read, Write};
 
 use hashbrown::HashMap;
 use meilisearch_schema::FieldId;
-use serde::{Deserialize, Serialize};
+use crate::{DocumentId, GeoPoint, Number};
 
-use crate::{DocumentId, Number};
+pub(crate) type RankedNumbers = HashMap<(DocumentId, FieldId), Number>;
+pub(crate) type GeoPoints = HashMap<DocumentId, GeoPoint>;
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(transparent)]
-pub struct RankedMap(HashMap<(DocumentId, FieldId), Number>);
+/// The values the documents are sorted on, the numbers of the attributes registered
+/// for ranking and the locations of the documents.
+///
+/// The numbers and the locations are stored under different keys of the main store,
+/// the numbers keep the format they had before the locations were introduced.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RankedMap {
+    numbers: RankedNumbers,
+    geo_points: GeoPoints,
+}
 
 impl RankedMap {
+    pub(crate) fn from_parts(numbers: RankedNumbers, geo_points: GeoPoints) -> RankedMap {
+        RankedMap { numbers, geo_points }
+    }
+
+    pub(crate) fn numbers(&self) -> &RankedNumbers {
+        &self.numbers
+    }
+
+    pub(crate) fn geo_points(&self) -> &GeoPoints {
+        &self.geo_points
+    }
+
+    /// The number of values stored, numbers and locations.
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.numbers.len() + self.geo_points.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        self.numbers.is_empty() && self.geo_points.is_empty()
     }
 
     pub fn insert(&mut self, document: DocumentId, field: FieldId, number: Number) {
-        self.0.insert((document, field), number);
+        self.numbers.insert((document, field), number);
     }
 
     pub fn remove(&mut self, document: DocumentId, field: FieldId) {
-        self.0.remove(&(document, field));
+        self.numbers.remove(&(document, field));
     }
 
     pub fn get(&self, document: DocumentId, field: FieldId) -> Option<Number> {
-        self.0.get(&(document, field)).cloned()
+        self.numbers.get(&(document, field)).cloned()
+    }
+
+    pub fn insert_geo_point(&mut self, document: DocumentId, point: GeoPoint) {
+        self.geo_points.insert(document, point);
+    }
+
+    pub fn remove_geo_point(&mut self, document: DocumentId) {
+        self.geo_points.remove(&document);
+    }
+
+    pub fn geo_point(&self, document: DocumentId) -> Option<GeoPoint> {
+        self.geo_points.get(&document).cloned()
     }
 
+    /// Reads the numbers only, the locations are stored separately.
     pub fn read_from_bin<R: Read>(reader: R) -> bincode::Result<RankedMap> {
-        bincode::deserialize_from(reader).map(RankedMap)
+        bincode::deserialize_from(reader).map(|numbers| RankedMap::from_parts(numbers, GeoPoints::new()))
     }
 
+    /// Writes the numbers only, the locations are stored separately.
     pub fn write_to_bin<W: Write>(&self, writer: W) -> bincode::Result<()> {
-        bincode::serialize_into(writer, &self.0)
+        bincode::serialize_into(writer, &self.numbers)
     }
 }
@@ -5,6 +5,8 @@ use std::iter::IntoIterator;
 use serde::{Deserialize, Deserializer, Serialize};
 use once_cell::sync::Lazy;
 
+use crate::GeoPoint;
+
 use self::RankingRule::*;
 
 pub const DEFAULT_RANKING_RULES: [RankingRule; 6] = [Typo, Words, Proximity, Attribute, WordsPosition, Exactness];
@@ -13,6 +15,10 @@ static RANKING_RULE_REGEX: Lazy<regex::Regex> = Lazy::new(|| {
     regex::Regex::new(r"(asc|desc)\(([a-zA-Z0-9-_]*)\)").unwrap()
 });
 
+static GEO_RANKING_RULE_REGEX: Lazy<regex::Regex> = Lazy::new(|| {
+    regex::Regex::new(r"^_geoPoint\(([^)]*)\)$").unwrap()
+});
+
 #[derive(Default, Clone, Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct Settings {
@@ -30,6 +36,12 @@ pub struct Settings {
     pub synonyms: Option<Option<BTreeMap<String, Vec<String>>>>,
     #[serde(default, deserialize_with = "deserialize_some")]
     pub attributes_for_faceting: Option<Option<Vec<String>>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub sortable_attributes: Option<Option<Vec<String>>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub typo_tolerance: Option<Option<TypoTolerance>>,
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub query_rules: Option<Option<Vec<QueryRule>>>,
 }
 
 // Any value that is present is considered Some value, including null.
@@ -59,10 +71,187 @@ impl Settings {
             stop_words: settings.stop_words.into(),
             synonyms: settings.synonyms.into(),
             attributes_for_faceting: settings.attributes_for_faceting.into(),
+            sortable_attributes: settings.sortable_attributes.into(),
+            typo_tolerance: settings.typo_tolerance.into(),
+            query_rules: settings.query_rules.into(),
         })
     }
 }
 
+/// The number of typos allowed for the query words, given the length of the words in bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct MinWordSizeForTypos {
+    #[serde(default = "default_one_typo")]
+    pub one_typo: usize,
+    #[serde(default = "default_two_typos")]
+    pub two_typos: usize,
+}
+
+fn default_one_typo() -> usize { 5 }
+
+fn default_two_typos() -> usize { 9 }
+
+impl Default for MinWordSizeForTypos {
+    fn default() -> MinWordSizeForTypos {
+        MinWordSizeForTypos { one_typo: default_one_typo(), two_typos: default_two_typos() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TypoTolerance {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub min_word_size_for_typos: MinWordSizeForTypos,
+    /// The words are normalized like the query words when the settings are applied.
+    #[serde(default)]
+    pub disable_on_words: BTreeSet<String>,
+    #[serde(default)]
+    pub disable_on_attributes: BTreeSet<String>,
+}
+
+fn default_enabled() -> bool { true }
+
+impl Default for TypoTolerance {
+    fn default() -> TypoTolerance {
+        TypoTolerance {
+            enabled: default_enabled(),
+            min_word_size_for_typos: MinWordSizeForTypos::default(),
+            disable_on_words: BTreeSet::new(),
+            disable_on_attributes: BTreeSet::new(),
+        }
+    }
+}
+
+impl TypoTolerance {
+    /// Returns the maximum number of typos allowed for this query word.
+    pub fn max_typos(&self, word: &str) -> u8 {
+        let sizes = &self.min_word_size_for_typos;
+        if !self.enabled || self.disable_on_words.contains(word) {
+            0
+        } else if word.len() >= sizes.two_typos {
+            2
+        } else if word.len() >= sizes.one_typo {
+            1
+        } else {
+            0
+        }
+    }
+
+    pub fn check(&self) -> Result<(), TypoToleranceError> {
+        let sizes = &self.min_word_size_for_typos;
+        if sizes.one_typo > sizes.two_typos {
+            return Err(TypoToleranceError);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TypoToleranceError;
+
+impl std::fmt::Display for TypoToleranceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "the minimum word size for one typo must be less than or equal to the one for two typos")
+    }
+}
+
+/// A rule changing the results of the searches matching all of its conditions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct QueryRule {
+    pub id: String,
+    #[serde(default)]
+    pub conditions: QueryRuleConditions,
+    #[serde(default)]
+    pub consequences: QueryRuleConsequences,
+}
+
+/// The conditions of a rule, a rule without conditions applies to every search.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct QueryRuleConditions {
+    /// The query must be this one, ignoring the case and the spacing.
+    #[serde(default)]
+    pub query_is: Option<String>,
+    /// The query must contain this word, ignoring the case.
+    #[serde(default)]
+    pub query_contains: Option<String>,
+    /// The filters of the search must contain this expression.
+    #[serde(default)]
+    pub filters_contain: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct QueryRuleConsequences {
+    /// The documents placed at the given positions, whether they match the search or not.
+    #[serde(default)]
+    pub pin: Vec<PinnedDocument>,
+    /// The ids of the documents removed from the results.
+    #[serde(default)]
+    pub hide: Vec<String>,
+    /// A filter expression combined with the filters of the search.
+    #[serde(default)]
+    pub filters: Option<String>,
+    /// The query searched in place of the one given.
+    #[serde(default)]
+    pub rewrite_query: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PinnedDocument {
+    pub id: String,
+    /// The position of the document in the results, starting at 0.
+    pub position: usize,
+}
+
+impl QueryRule {
+    /// Returns whether the rule applies to a search with this query and these filters.
+    pub fn matches(&self, query: Option<&str>, filters: Option<&str>) -> bool {
+        fn normalize(s: &str) -> String {
+            s.split_whitespace().map(str::to_lowercase).collect::<Vec<_>>().join(" ")
+        }
+
+        let conditions = &self.conditions;
+        let query_is = conditions.query_is.as_ref().map_or(true, |expected| {
+            query.map_or(false, |query| normalize(query) == normalize(expected))
+        });
+        let query_contains = conditions.query_contains.as_ref().map_or(true, |word| {
+            let word = word.trim().to_lowercase();
+            query.map_or(false, |query| query.split_whitespace().any(|w| w.to_lowercase() == word))
+        });
+        let filters_contain = conditions.filters_contain.as_ref().map_or(true, |expression| {
+            filters.map_or(false, |filters| normalize(filters).contains(&normalize(expression)))
+        });
+
+        query_is && query_contains && filters_contain
+    }
+
+    /// Checks that the ids of the rules are unique.
+    pub fn check_all(rules: &[QueryRule]) -> Result<(), QueryRuleError> {
+        let mut ids = BTreeSet::new();
+        for rule in rules {
+            if !ids.insert(rule.id.as_str()) {
+                return Err(QueryRuleError(rule.id.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryRuleError(String);
+
+impl std::fmt::Display for QueryRuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "the query rule id {:?} is used by several rules", self.0)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum UpdateState<T> {
     Update(T),
@@ -99,6 +288,8 @@ pub enum RankingRule {
     Exactness,
     Asc(String),
     Desc(String),
+    /// Sorts the documents by their distance to the point, the closest first.
+    GeoDistance(GeoPoint),
 }
 
 impl std::fmt::Display for RankingRule {
@@ -112,6 +303,7 @@ impl std::fmt::Display for RankingRule {
             RankingRule::Exactness => f.write_str("exactness"),
             RankingRule::Asc(field) => write!(f, "asc({})", field),
             RankingRule::Desc(field) => write!(f, "desc({})", field),
+            RankingRule::GeoDistance(point) => write!(f, "_geoPoint({})", point),
         }
     }
 }
@@ -127,6 +319,11 @@ impl FromStr for RankingRule {
             "attribute" => RankingRule::Attribute,
             "wordsPosition" => RankingRule::WordsPosition,
             "exactness" => RankingRule::Exactness,
+            _ if s.starts_with("_geoPoint(") => {
+                let captures = GEO_RANKING_RULE_REGEX.captures(s).ok_or(RankingRuleConversionError)?;
+                let point = captures[1].parse().map_err(|_| RankingRuleConversionError)?;
+                RankingRule::GeoDistance(point)
+            }
             _ => {
                 let captures = RANKING_RULE_REGEX.captures(s).ok_or(RankingRuleConversionError)?;
                 match (captures.get(1).map(|m| m.as_str()), captures.get(2)) {
@@ -148,11 +345,39 @@ impl RankingRule {
         }
     }
 
+    /// Returns whether the rule sorts the documents by one of their values,
+    /// rather than by how they match the query.
+    pub fn is_custom(&self) -> bool {
+        matches!(self, RankingRule::Asc(_) | RankingRule::Desc(_) | RankingRule::GeoDistance(_))
+    }
+
     pub fn try_from_iter(rules: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Vec<RankingRule>, RankingRuleConversionError> {
         rules.into_iter()
             .map(|s| RankingRule::from_str(s.as_ref()))
             .collect()
     }
+
+    /// Checks that a rule is not given several times.
+    pub fn check_all(rules: &[RankingRule]) -> Result<(), RankingRuleError> {
+        let mut names = BTreeSet::new();
+        for rule in rules {
+            let name = rule.to_string();
+            if names.contains(&name) {
+                return Err(RankingRuleError(name));
+            }
+            names.insert(name);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RankingRuleError(String);
+
+impl std::fmt::Display for RankingRuleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "the ranking rule {} is given several times", self.0)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -165,6 +390,9 @@ pub struct SettingsUpdate {
     pub stop_words: UpdateState<BTreeSet<String>>,
     pub synonyms: UpdateState<BTreeMap<String, Vec<String>>>,
     pub attributes_for_faceting: UpdateState<Vec<String>>,
+    pub sortable_attributes: UpdateState<Vec<String>>,
+    pub typo_tolerance: UpdateState<TypoTolerance>,
+    pub query_rules: UpdateState<Vec<QueryRule>>,
 }
 
 impl Default for SettingsUpdate {
@@ -178,6 +406,9 @@ impl Default for SettingsUpdate {
             stop_words: UpdateState::Nothing,
             synonyms: UpdateState::Nothing,
             attributes_for_faceting: UpdateState::Nothing,
+            sortable_attributes: UpdateState::Nothing,
+            typo_tolerance: UpdateState::Nothing,
+            query_rules: UpdateState::Nothing,
         }
     }
 }
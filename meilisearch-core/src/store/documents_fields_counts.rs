@@ -1,3 +1,5 @@
+use std::ops::Bound;
+
 use super::DocumentFieldIndexedKey;
 use crate::database::MainT;
 use crate::DocumentId;
@@ -62,7 +64,21 @@ impl DocumentsFieldsCounts {
     }
 
     pub fn documents_ids<'txn>(self, reader: &'txn heed::RoTxn<MainT>) -> MResult<DocumentsIdsIter<'txn>> {
-        let iter = self.documents_fields_counts.iter(reader)?;
+        let iter = self.documents_fields_counts.range(reader, &(..))?;
+        Ok(DocumentsIdsIter {
+            last_seen_id: None,
+            iter,
+        })
+    }
+
+    /// Iterates over the ids of the documents that come after the given one.
+    pub fn documents_ids_after<'txn>(
+        self,
+        reader: &'txn heed::RoTxn<MainT>,
+        document_id: DocumentId,
+    ) -> MResult<DocumentsIdsIter<'txn>> {
+        let start = DocumentFieldIndexedKey::new(document_id, IndexedPos::max());
+        let iter = self.documents_fields_counts.range(reader, &(Bound::Excluded(start), Bound::Unbounded))?;
         Ok(DocumentsIdsIter {
             last_seen_id: None,
             iter,
@@ -99,7 +115,7 @@ impl Iterator for DocumentFieldsCountsIter<'_> {
 
 pub struct DocumentsIdsIter<'txn> {
     last_seen_id: Option<DocumentId>,
-    iter: heed::RoIter<'txn, OwnedType<DocumentFieldIndexedKey>, OwnedType<u16>>,
+    iter: heed::RoRange<'txn, OwnedType<DocumentFieldIndexedKey>, OwnedType<u16>>,
 }
 
 impl Iterator for DocumentsIdsIter<'_> {
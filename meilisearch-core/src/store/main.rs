@@ -9,7 +9,8 @@ use sdset::Set;
 
 use crate::database::MainT;
 use crate::{RankedMap, MResult};
-use crate::settings::RankingRule;
+use crate::ranked_map::{GeoPoints, RankedNumbers};
+use crate::settings::{QueryRule, RankingRule, TypoTolerance};
 use crate::{FstSetCow, FstMapCow};
 use super::{CowSet, DocumentsIds};
 
@@ -19,15 +20,20 @@ const CUSTOMS_KEY: &str = "customs";
 const DISTINCT_ATTRIBUTE_KEY: &str = "distinct-attribute";
 const EXTERNAL_DOCIDS_KEY: &str = "external-docids";
 const FIELDS_DISTRIBUTION_KEY: &str = "fields-distribution";
+const GEO_POINTS_KEY: &str = "geo-points";
 const INTERNAL_DOCIDS_KEY: &str = "internal-docids";
 const NAME_KEY: &str = "name";
 const NUMBER_OF_DOCUMENTS_KEY: &str = "number-of-documents";
+const QUERY_RULES_KEY: &str = "query-rules";
 const RANKED_MAP_KEY: &str = "ranked-map";
 const RANKING_RULES_KEY: &str = "ranking-rules";
 const SCHEMA_KEY: &str = "schema";
+const SEARCH_EVENTS_KEY: &str = "search-events";
+const SORTABLE_ATTRIBUTES_KEY: &str = "sortable-attributes";
 const SORTED_DOCUMENT_IDS_CACHE_KEY: &str = "sorted-document-ids-cache";
 const STOP_WORDS_KEY: &str = "stop-words";
 const SYNONYMS_KEY: &str = "synonyms";
+const TYPO_TOLERANCE_KEY: &str = "typo-tolerance";
 const UPDATED_AT_KEY: &str = "updated-at";
 const WORDS_KEY: &str = "words";
 
@@ -187,11 +193,18 @@ impl Main {
     }
 
     pub fn put_ranked_map(self, writer: &mut heed::RwTxn<MainT>, ranked_map: &RankedMap) -> MResult<()> {
-        Ok(self.main.put::<_, Str, SerdeBincode<RankedMap>>(writer, RANKED_MAP_KEY, &ranked_map)?)
+        self.main.put::<_, Str, SerdeBincode<RankedNumbers>>(writer, RANKED_MAP_KEY, ranked_map.numbers())?;
+        Ok(self.main.put::<_, Str, SerdeBincode<GeoPoints>>(writer, GEO_POINTS_KEY, ranked_map.geo_points())?)
     }
 
     pub fn ranked_map(self, reader: &heed::RoTxn<MainT>) -> MResult<Option<RankedMap>> {
-        Ok(self.main.get::<_, Str, SerdeBincode<RankedMap>>(reader, RANKED_MAP_KEY)?)
+        let numbers = match self.main.get::<_, Str, SerdeBincode<RankedNumbers>>(reader, RANKED_MAP_KEY)? {
+            Some(numbers) => numbers,
+            None => return Ok(None),
+        };
+        // the indexes created before the locations were introduced have none
+        let geo_points = self.main.get::<_, Str, SerdeBincode<GeoPoints>>(reader, GEO_POINTS_KEY)?;
+        Ok(Some(RankedMap::from_parts(numbers, geo_points.unwrap_or_default())))
     }
 
     pub fn put_synonyms_fst<A: AsRef<[u8]>>(self, writer: &mut heed::RwTxn<MainT>, fst: &fst::Set<A>) -> MResult<()> {
@@ -295,6 +308,42 @@ impl Main {
         Ok(self.main.delete::<_, Str>(writer, RANKING_RULES_KEY)?)
     }
 
+    pub fn sortable_attributes(&self, reader: &heed::RoTxn<MainT>) -> MResult<Option<Vec<String>>> {
+        Ok(self.main.get::<_, Str, SerdeBincode<Vec<String>>>(reader, SORTABLE_ATTRIBUTES_KEY)?)
+    }
+
+    pub fn put_sortable_attributes(self, writer: &mut heed::RwTxn<MainT>, value: &[String]) -> MResult<()> {
+        Ok(self.main.put::<_, Str, SerdeBincode<Vec<String>>>(writer, SORTABLE_ATTRIBUTES_KEY, &value.to_vec())?)
+    }
+
+    pub fn delete_sortable_attributes(self, writer: &mut heed::RwTxn<MainT>) -> MResult<bool> {
+        Ok(self.main.delete::<_, Str>(writer, SORTABLE_ATTRIBUTES_KEY)?)
+    }
+
+    pub fn typo_tolerance(&self, reader: &heed::RoTxn<MainT>) -> MResult<Option<TypoTolerance>> {
+        Ok(self.main.get::<_, Str, SerdeBincode<TypoTolerance>>(reader, TYPO_TOLERANCE_KEY)?)
+    }
+
+    pub fn put_typo_tolerance(self, writer: &mut heed::RwTxn<MainT>, value: &TypoTolerance) -> MResult<()> {
+        Ok(self.main.put::<_, Str, SerdeBincode<TypoTolerance>>(writer, TYPO_TOLERANCE_KEY, value)?)
+    }
+
+    pub fn delete_typo_tolerance(self, writer: &mut heed::RwTxn<MainT>) -> MResult<bool> {
+        Ok(self.main.delete::<_, Str>(writer, TYPO_TOLERANCE_KEY)?)
+    }
+
+    pub fn query_rules(&self, reader: &heed::RoTxn<MainT>) -> MResult<Option<Vec<QueryRule>>> {
+        Ok(self.main.get::<_, Str, SerdeBincode<Vec<QueryRule>>>(reader, QUERY_RULES_KEY)?)
+    }
+
+    pub fn put_query_rules(self, writer: &mut heed::RwTxn<MainT>, value: &[QueryRule]) -> MResult<()> {
+        Ok(self.main.put::<_, Str, SerdeBincode<Vec<QueryRule>>>(writer, QUERY_RULES_KEY, &value.to_vec())?)
+    }
+
+    pub fn delete_query_rules(self, writer: &mut heed::RwTxn<MainT>) -> MResult<bool> {
+        Ok(self.main.delete::<_, Str>(writer, QUERY_RULES_KEY)?)
+    }
+
     pub fn distinct_attribute(&self, reader: &heed::RoTxn<MainT>) -> MResult<Option<FieldId>> {
         match self.main.get::<_, Str, OwnedType<u16>>(reader, DISTINCT_ATTRIBUTE_KEY)? {
             Some(value) => Ok(Some(FieldId(value.to_owned()))),
@@ -317,4 +366,13 @@ impl Main {
     pub fn customs<'txn>(self, reader: &'txn heed::RoTxn<MainT>) -> MResult<Option<&'txn [u8]>> {
         Ok(self.main.get::<_, Str, ByteSlice>(reader, CUSTOMS_KEY)?)
     }
+
+    /// The searches made on the index, serialized by the server that records them.
+    pub fn put_search_events(self, writer: &mut heed::RwTxn<MainT>, events: &[u8]) -> MResult<()> {
+        Ok(self.main.put::<_, Str, ByteSlice>(writer, SEARCH_EVENTS_KEY, events)?)
+    }
+
+    pub fn search_events<'txn>(self, reader: &'txn heed::RoTxn<MainT>) -> MResult<Option<&'txn [u8]>> {
+        Ok(self.main.get::<_, Str, ByteSlice>(reader, SEARCH_EVENTS_KEY)?)
+    }
 }
@@ -11,6 +11,7 @@ mod prefix_postings_lists_cache;
 mod synonyms;
 mod updates;
 mod updates_results;
+mod vectors;
 
 pub use self::cow_set::CowSet;
 pub use self::docs_words::DocsWords;
@@ -25,21 +26,27 @@ pub use self::prefix_postings_lists_cache::PrefixPostingsListsCache;
 pub use self::synonyms::Synonyms;
 pub use self::updates::Updates;
 pub use self::updates_results::UpdatesResults;
+pub use self::vectors::{Vectors, VectorsIter};
 
 use std::borrow::Cow;
+use std::cmp::{self, Reverse};
 use std::collections::HashSet;
 use std::convert::TryInto;
 use std::{mem, ptr};
 
+use fst::{IntoStreamer, Streamer};
 use heed::{BytesEncode, BytesDecode};
+use meilisearch_tokenizer::analyzer::{Analyzer, AnalyzerConfig};
 use meilisearch_schema::{IndexedPos, FieldId};
 use sdset::{Set, SetBuf};
 use serde::de::{self, Deserialize};
 use zerocopy::{AsBytes, FromBytes};
 
+use crate::automaton::{build_dfa, build_prefix_dfa};
 use crate::criterion::Criteria;
 use crate::database::{MainT, UpdateT};
 use crate::database::{UpdateEvent, UpdateEventsEmitter};
+use crate::query_tree::is_excluded_word;
 use crate::serde::Deserializer;
 use crate::settings::SettingsUpdate;
 use crate::{query_builder::QueryBuilder, update, DocIndex, DocumentId, Error, MResult};
@@ -48,6 +55,12 @@ type BEU32 = zerocopy::U32<byteorder::BigEndian>;
 type BEU64 = zerocopy::U64<byteorder::BigEndian>;
 pub type BEU16 = zerocopy::U16<byteorder::BigEndian>;
 
+/// The longest prefixes whose postings lists are cached, see `compute_short_prefixes`.
+const SUGGESTIONS_CACHED_PREFIX_LEN: usize = 2;
+
+/// The number of words starting with a prefix that are looked at to suggest completions.
+const SUGGESTIONS_MAX_CANDIDATES: usize = 1000;
+
 #[derive(Debug, Copy, Clone, AsBytes, FromBytes)]
 #[repr(C)]
 pub struct DocumentFieldIndexedKey {
@@ -205,6 +218,10 @@ fn facets_name(name: &str) -> String {
     format!("store-{}-facets", name)
 }
 
+fn vectors_name(name: &str) -> String {
+    format!("store-{}-vectors", name)
+}
+
 #[derive(Clone)]
 pub struct Index {
     pub main: Main,
@@ -214,6 +231,7 @@ pub struct Index {
     pub facets: Facets,
     pub synonyms: Synonyms,
     pub docs_words: DocsWords,
+    pub vectors: Vectors,
     pub prefix_documents_cache: PrefixDocumentsCache,
     pub prefix_postings_lists_cache: PrefixPostingsListsCache,
 
@@ -278,6 +296,222 @@ impl Index {
         }
     }
 
+    /// Returns the words of the index that start with the given prefix along with the number
+    /// of documents they are found in, the most frequent words first. The stop words are
+    /// never suggested.
+    ///
+    /// When attributes are given, only the occurrences of the words in these attributes
+    /// are counted, and the words not found in any of them are not suggested.
+    ///
+    /// Only the first `SUGGESTIONS_MAX_CANDIDATES` words starting with the prefix, in
+    /// lexicographic order, are considered.
+    pub fn suggestions(
+        &self,
+        reader: &heed::RoTxn<MainT>,
+        prefix: &str,
+        attributes: Option<&HashSet<IndexedPos>>,
+        limit: usize,
+    ) -> MResult<Vec<(String, usize)>> {
+        let words = self.main.words_fst(reader)?;
+        let stop_words = self.main.stop_words_fst(reader)?;
+
+        // the prefix is normalized like the indexed words, only its last word is completed
+        let no_stop_words = fst::Set::<Vec<u8>>::default();
+        let analyzer = Analyzer::new(AnalyzerConfig::default_with_stopwords(&no_stop_words));
+        let analyzed = analyzer.analyze(prefix);
+        let prefix = match analyzed.tokens().filter(|t| t.is_word()).last() {
+            Some(token) => token.word.to_string(),
+            None => return Ok(Vec::new()),
+        };
+
+        // the short prefixes are cached along with the postings lists of the words that are
+        // longer than them, when none of these words can be suggested only the prefix can
+        let only_prefix = if prefix.len() <= SUGGESTIONS_CACHED_PREFIX_LEN {
+            let mut cached_prefix = [0; 4];
+            cached_prefix[..prefix.len()].copy_from_slice(prefix.as_bytes());
+            match self.prefix_postings_lists_cache.prefix_postings_list(reader, cached_prefix)? {
+                Some(postings_list) => match attributes {
+                    Some(attributes) => !postings_list
+                        .matches
+                        .iter()
+                        .any(|m| attributes.contains(&IndexedPos(m.attribute))),
+                    None => false,
+                },
+                None => true,
+            }
+        } else {
+            false
+        };
+
+        let mut suggestions = Vec::new();
+        let mut candidates = 0;
+        let mut stream = words.range().ge(&prefix).into_stream();
+        while let Some(word) = stream.next() {
+            // the words are sorted, the ones that follow can't start with the prefix
+            if !word.starts_with(prefix.as_bytes()) || (only_prefix && word != prefix.as_bytes()) {
+                break;
+            }
+
+            if stop_words.contains(word) {
+                continue;
+            }
+
+            candidates += 1;
+            if candidates > SUGGESTIONS_MAX_CANDIDATES {
+                break;
+            }
+
+            let postings_list = match self.postings_lists.postings_list(reader, word)? {
+                Some(postings_list) => postings_list,
+                None => continue,
+            };
+
+            let count = match attributes {
+                Some(attributes) => {
+                    let mut docids: Vec<_> = postings_list
+                        .matches
+                        .iter()
+                        .filter(|m| attributes.contains(&IndexedPos(m.attribute)))
+                        .map(|m| m.document_id)
+                        .collect();
+                    docids.dedup();
+                    docids.len()
+                }
+                None => postings_list.docids.len(),
+            };
+
+            if count != 0 {
+                if let Ok(word) = std::str::from_utf8(word) {
+                    suggestions.push((word.to_string(), count));
+                }
+            }
+        }
+
+        suggestions.sort_unstable_by(|(aw, ac), (bw, bc)| bc.cmp(ac).then_with(|| aw.cmp(bw)));
+        suggestions.truncate(limit);
+
+        Ok(suggestions)
+    }
+
+    /// Returns the most distinctive words of a document, ranked by inverse document
+    /// frequency, the words found in the fewest documents first. The stop words and
+    /// the words only found in this document are ignored, they can't bring similar ones,
+    /// so are the words containing digits, like identifiers and dates.
+    pub fn distinctive_words(
+        &self,
+        reader: &heed::RoTxn<MainT>,
+        document_id: DocumentId,
+        limit: usize,
+    ) -> MResult<Vec<String>> {
+        let words = self.docs_words.doc_words(reader, document_id)?;
+        let stop_words = self.main.stop_words_fst(reader)?;
+
+        let mut candidates = Vec::new();
+        let mut stream = words.stream();
+        while let Some(word) = stream.next() {
+            if stop_words.contains(word) || word.iter().any(u8::is_ascii_digit) {
+                continue;
+            }
+
+            let frequency = match self.postings_lists.postings_list(reader, word)? {
+                Some(postings_list) => postings_list.docids.len(),
+                None => continue,
+            };
+
+            if frequency > 1 {
+                if let Ok(word) = std::str::from_utf8(word) {
+                    candidates.push((frequency, word.to_string()));
+                }
+            }
+        }
+
+        candidates.sort_unstable();
+        candidates.truncate(limit);
+
+        Ok(candidates.into_iter().map(|(_, word)| word).collect())
+    }
+
+    /// Returns the query with its unknown words replaced by the most likely words of the
+    /// index, or `None` if all the words of the query are known.
+    ///
+    /// The candidates of a word are searched with one more typo than the search tolerates,
+    /// up to two, the closest and then the most frequent candidate is kept. The last word
+    /// of the query is considered as a prefix, like during the search.
+    ///
+    /// The words are replaced in place, the quotes of the phrases and the excluded words
+    /// are kept as they are.
+    pub fn correct_query(&self, reader: &heed::RoTxn<MainT>, query: &str) -> MResult<Option<String>> {
+        let words = self.main.words_fst(reader)?;
+        let stop_words = self.main.stop_words_fst(reader)?;
+        let typo_tolerance = self.main.typo_tolerance(reader)?.unwrap_or_default();
+
+        let no_stop_words = fst::Set::<Vec<u8>>::default();
+        let analyzer = Analyzer::new(AnalyzerConfig::default_with_stopwords(&no_stop_words));
+        let analyzed = analyzer.analyze(query);
+        let query_words: Vec<_> = analyzed
+            .tokens()
+            .filter(|t| t.is_word() && !is_excluded_word(query, t.byte_start))
+            .map(|t| (t.byte_start..t.byte_end, t.word.to_string()))
+            .collect();
+
+        let mut corrections = Vec::new();
+        for (i, (range, word)) in query_words.iter().enumerate() {
+            let is_prefix = i + 1 == query_words.len();
+            let is_known = if is_prefix {
+                let mut stream = words.range().ge(word).into_stream();
+                stream.next().map_or(false, |w| w.starts_with(word.as_bytes()))
+            } else {
+                words.contains(word)
+            };
+
+            if is_known || stop_words.contains(word) {
+                continue;
+            }
+
+            let typos = cmp::min(typo_tolerance.max_typos(word) + 1, 2);
+            let dfa = if is_prefix { build_prefix_dfa(word, typos) } else { build_dfa(word, typos) };
+
+            let mut best: Option<(u8, usize, Vec<u8>)> = None;
+            let mut stream = words.search(&dfa).into_stream();
+            while let Some(candidate) = stream.next() {
+                if stop_words.contains(candidate) {
+                    continue;
+                }
+
+                let frequency = match self.postings_lists.postings_list(reader, candidate)? {
+                    Some(postings_list) if !postings_list.docids.is_empty() => postings_list.docids.len(),
+                    _ => continue,
+                };
+
+                // the closest candidates are the most likely, then the most frequent ones
+                let distance = dfa.eval(candidate).to_u8();
+                let is_better = best.as_ref().map_or(true, |(d, f, _)| (distance, Reverse(frequency)) < (*d, Reverse(*f)));
+                if is_better {
+                    best = Some((distance, frequency, candidate.to_vec()));
+                }
+            }
+
+            if let Some(candidate) = best.and_then(|(_, _, candidate)| String::from_utf8(candidate).ok()) {
+                corrections.push((range.clone(), candidate));
+            }
+        }
+
+        if corrections.is_empty() {
+            return Ok(None);
+        }
+
+        let mut corrected = String::with_capacity(query.len());
+        let mut last_end = 0;
+        for (range, candidate) in corrections {
+            corrected.push_str(&query[last_end..range.start]);
+            corrected.push_str(&candidate);
+            last_end = range.end;
+        }
+        corrected.push_str(&query[last_end..]);
+
+        Ok(Some(corrected))
+    }
+
     pub fn customs_update(&self, writer: &mut heed::RwTxn<UpdateT>, customs: Vec<u8>) -> MResult<u64> {
         let _ = self.updates_notifier.send(UpdateEvent::NewUpdate);
         Ok(update::push_customs_update(writer, self.updates, self.updates_results, customs)?)
@@ -390,6 +624,7 @@ pub fn create(
     let updates_name = updates_name(name);
     let updates_results_name = updates_results_name(name);
     let facets_name = facets_name(name);
+    let vectors_name = vectors_name(name);
 
     // open all the stores
     let main = env.create_poly_database(Some(&main_name))?;
@@ -399,6 +634,7 @@ pub fn create(
     let facets = env.create_database(Some(&facets_name))?;
     let synonyms = env.create_database(Some(&synonyms_name))?;
     let docs_words = env.create_database(Some(&docs_words_name))?;
+    let vectors = env.create_database(Some(&vectors_name))?;
     let prefix_documents_cache = env.create_database(Some(&prefix_documents_cache_name))?;
     let prefix_postings_lists_cache = env.create_database(Some(&prefix_postings_lists_cache_name))?;
     let updates = update_env.create_database(Some(&updates_name))?;
@@ -411,6 +647,7 @@ pub fn create(
         documents_fields_counts: DocumentsFieldsCounts { documents_fields_counts },
         synonyms: Synonyms { synonyms },
         docs_words: DocsWords { docs_words },
+        vectors: Vectors { vectors },
         prefix_postings_lists_cache: PrefixPostingsListsCache { prefix_postings_lists_cache },
         prefix_documents_cache: PrefixDocumentsCache { prefix_documents_cache },
         facets: Facets { facets },
@@ -436,6 +673,7 @@ pub fn open(
     let docs_words_name = docs_words_name(name);
     let prefix_documents_cache_name = prefix_documents_cache_name(name);
     let facets_name = facets_name(name);
+    let vectors_name = vectors_name(name);
     let prefix_postings_lists_cache_name = prefix_postings_lists_cache_name(name);
     let updates_name = updates_name(name);
     let updates_results_name = updates_results_name(name);
@@ -465,6 +703,11 @@ pub fn open(
         Some(docs_words) => docs_words,
         None => return Ok(None),
     };
+    // the vectors store is missing from the indexes created before it existed
+    let vectors = match env.open_database(Some(&vectors_name))? {
+        Some(vectors) => vectors,
+        None => env.create_database(Some(&vectors_name))?,
+    };
     let prefix_documents_cache = match env.open_database(Some(&prefix_documents_cache_name))? {
         Some(prefix_documents_cache) => prefix_documents_cache,
         None => return Ok(None),
@@ -493,6 +736,7 @@ pub fn open(
         documents_fields_counts: DocumentsFieldsCounts { documents_fields_counts },
         synonyms: Synonyms { synonyms },
         docs_words: DocsWords { docs_words },
+        vectors: Vectors { vectors },
         prefix_documents_cache: PrefixDocumentsCache { prefix_documents_cache },
         facets: Facets { facets },
         prefix_postings_lists_cache: PrefixPostingsListsCache { prefix_postings_lists_cache },
@@ -514,6 +758,7 @@ pub fn clear(
     index.documents_fields_counts.clear(writer)?;
     index.synonyms.clear(writer)?;
     index.docs_words.clear(writer)?;
+    index.vectors.clear(writer)?;
     index.prefix_documents_cache.clear(writer)?;
     index.prefix_postings_lists_cache.clear(writer)?;
     index.updates.clear(update_writer)?;
@@ -0,0 +1,69 @@
+use std::convert::TryInto;
+
+use heed::Result as ZResult;
+use heed::types::{ByteSlice, OwnedType};
+
+use crate::database::MainT;
+use crate::DocumentId;
+use super::BEU32;
+
+/// The embeddings of the documents, stored as little endian `f32`s.
+#[derive(Copy, Clone)]
+pub struct Vectors {
+    pub(crate) vectors: heed::Database<OwnedType<BEU32>, ByteSlice>,
+}
+
+impl Vectors {
+    pub fn put_vector(
+        self,
+        writer: &mut heed::RwTxn<MainT>,
+        document_id: DocumentId,
+        vector: &[f32],
+    ) -> ZResult<()> {
+        let document_id = BEU32::new(document_id.0);
+        let bytes: Vec<u8> = vector.iter().flat_map(|f| f.to_le_bytes().to_vec()).collect();
+        self.vectors.put(writer, &document_id, &bytes)
+    }
+
+    pub fn del_vector(self, writer: &mut heed::RwTxn<MainT>, document_id: DocumentId) -> ZResult<bool> {
+        let document_id = BEU32::new(document_id.0);
+        self.vectors.delete(writer, &document_id)
+    }
+
+    pub fn clear(self, writer: &mut heed::RwTxn<MainT>) -> ZResult<()> {
+        self.vectors.clear(writer)
+    }
+
+    pub fn vector(self, reader: &heed::RoTxn<MainT>, document_id: DocumentId) -> ZResult<Option<Vec<f32>>> {
+        let document_id = BEU32::new(document_id.0);
+        Ok(self.vectors.get(reader, &document_id)?.map(decode_vector))
+    }
+
+    pub fn iter<'txn>(self, reader: &'txn heed::RoTxn<MainT>) -> ZResult<VectorsIter<'txn>> {
+        let iter = self.vectors.iter(reader)?;
+        Ok(VectorsIter { iter })
+    }
+}
+
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect()
+}
+
+pub struct VectorsIter<'txn> {
+    iter: heed::RoIter<'txn, OwnedType<BEU32>, ByteSlice>,
+}
+
+impl Iterator for VectorsIter<'_> {
+    type Item = ZResult<(DocumentId, Vec<f32>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.iter.next() {
+            Some(Ok((document_id, bytes))) => Some(Ok((DocumentId(document_id.get()), decode_vector(bytes)))),
+            Some(Err(e)) => Some(Err(e)),
+            None => None,
+        }
+    }
+}
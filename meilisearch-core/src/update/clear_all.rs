@@ -16,6 +16,7 @@ pub fn apply_clear_all(
     index.documents_fields_counts.clear(writer)?;
     index.postings_lists.clear(writer)?;
     index.docs_words.clear(writer)?;
+    index.vectors.clear(writer)?;
     index.prefix_documents_cache.clear(writer)?;
     index.prefix_postings_lists_cache.clear(writer)?;
     index.facets.clear(writer)?;
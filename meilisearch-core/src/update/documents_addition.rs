@@ -14,10 +14,10 @@ use crate::database::{UpdateEvent, UpdateEventsEmitter};
 use crate::facets;
 use crate::raw_indexer::RawIndexer;
 use crate::serde::Deserializer;
-use crate::store::{self, DocumentsFields, DocumentsFieldsCounts, DiscoverIds};
+use crate::store::{self, DocumentsFields, DocumentsFieldsCounts, DiscoverIds, Vectors};
 use crate::update::helpers::{index_value, value_to_number, extract_document_id};
 use crate::update::{apply_documents_deletion, compute_short_prefixes, next_update_id, Update};
-use crate::{Error, MResult, RankedMap};
+use crate::{vector_from_value, Error, GeoPoint, MResult, RankedMap, GEO_FIELD, VECTOR_FIELD};
 
 pub struct DocumentsAddition<D> {
     updates_store: store::Updates,
@@ -114,6 +114,7 @@ fn index_document<A: AsRef<[u8]>>(
     writer: &mut heed::RwTxn<MainT>,
     documents_fields: DocumentsFields,
     documents_fields_counts: DocumentsFieldsCounts,
+    vectors: Vectors,
     ranked_map: &mut RankedMap,
     indexer: &mut RawIndexer<A>,
     schema: &Schema,
@@ -125,6 +126,22 @@ fn index_document<A: AsRef<[u8]>>(
     let serialized = serde_json::to_vec(value)?;
     documents_fields.put_document_field(writer, document_id, field_id, &serialized)?;
 
+    // the location is only used to filter and sort, its coordinates are not searchable
+    if schema.name(field_id) == Some(GEO_FIELD) {
+        if let Some(point) = GeoPoint::from_value(value) {
+            ranked_map.insert_geo_point(document_id, point);
+        }
+        return Ok(());
+    }
+
+    // the embedding is only compared to the query vector, it is not searchable either
+    if schema.name(field_id) == Some(VECTOR_FIELD) {
+        if let Some(vector) = vector_from_value(value) {
+            vectors.put_vector(writer, document_id, &vector)?;
+        }
+        return Ok(());
+    }
+
     if let Some(indexed_pos) = schema.is_searchable(field_id) {
         let number_of_words = index_value(indexer, document_id, indexed_pos, value);
         if let Some(number_of_words) = number_of_words {
@@ -232,6 +249,7 @@ pub fn apply_addition(
                 writer,
                 index.documents_fields,
                 index.documents_fields_counts,
+                index.vectors,
                 &mut ranked_map,
                 &mut indexer,
                 &schema,
@@ -337,6 +355,7 @@ pub fn reindex_all_documents(writer: &mut heed::RwTxn<MainT>, index: &store::Ind
                 writer,
                 index.documents_fields,
                 index.documents_fields_counts,
+                index.vectors,
                 &mut ranked_map,
                 &mut indexer,
                 &schema,
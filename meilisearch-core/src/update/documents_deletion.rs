@@ -113,6 +113,8 @@ pub fn apply_documents_deletion(
         for ranked_attr in ranked_fields {
             ranked_map.remove(id, *ranked_attr);
         }
+        ranked_map.remove_geo_point(id);
+        index.vectors.del_vector(writer, id)?;
 
         let words = index.docs_words.doc_words(writer, id)?;
         if !words.is_empty() {
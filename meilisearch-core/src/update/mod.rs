@@ -382,3 +382,26 @@ fn cache_document_ids_sorted(
     crate::bucket_sort::placeholder_document_sort(document_ids, index, writer, ranked_map)?;
     index.main.put_sorted_document_ids_cache(writer, &document_ids)
 }
+
+/// Sorts the cached document ids again if they are not sorted by the custom rules and then
+/// by id, the caches built before the ties were broken by the ids can't be used by cursors.
+pub fn resort_document_ids_cache(writer: &mut heed::RwTxn<MainT>, index: &store::Index) -> MResult<()> {
+    let mut document_ids = match index.main.sorted_document_ids_cache(writer)? {
+        Some(document_ids) => document_ids.into_owned(),
+        None => return Ok(()),
+    };
+
+    let ranking_rules = index.main.ranking_rules(writer)?.unwrap_or_default();
+    let schema = match index.main.schema(writer)? {
+        Some(schema) => schema,
+        None => return Ok(()),
+    };
+    let ranked_map = index.main.ranked_map(writer)?.unwrap_or_default();
+
+    if !crate::bucket_sort::custom_rules_is_sorted(&document_ids, &schema, &ranked_map, &ranking_rules) {
+        debug!("sorting the document ids cache again");
+        cache_document_ids_sorted(writer, &ranked_map, index, &mut document_ids)?;
+    }
+
+    Ok(())
+}
@@ -4,9 +4,10 @@ use heed::Result as ZResult;
 use fst::{set::OpBuilder, SetBuilder};
 use sdset::SetBuf;
 use meilisearch_schema::Schema;
+use meilisearch_tokenizer::analyzer::{Analyzer, AnalyzerConfig};
 
 use crate::database::{MainT, UpdateT};
-use crate::settings::{UpdateState, SettingsUpdate, RankingRule};
+use crate::settings::{UpdateState, SettingsUpdate, RankingRule, TypoTolerance};
 use crate::update::documents_addition::reindex_all_documents;
 use crate::update::{next_update_id, Update};
 use crate::{store, MResult, Error};
@@ -42,17 +43,61 @@ pub fn apply_settings_update(
         }
     };
 
+    let mut must_update_ranked = false;
+
     match settings.ranking_rules {
         UpdateState::Update(v) => {
-            let ranked_field: Vec<&str> = v.iter().filter_map(RankingRule::field).collect();
-            schema.update_ranked(&ranked_field)?;
             index.main.put_ranking_rules(writer, &v)?;
-            must_reindex = true;
+            must_update_ranked = true;
         },
         UpdateState::Clear => {
             index.main.delete_ranking_rules(writer)?;
-            schema.clear_ranked();
-            must_reindex = true;
+            must_update_ranked = true;
+        },
+        UpdateState::Nothing => (),
+    }
+
+    match settings.sortable_attributes {
+        UpdateState::Update(v) => {
+            index.main.put_sortable_attributes(writer, &v)?;
+            must_update_ranked = true;
+        },
+        UpdateState::Clear => {
+            index.main.delete_sortable_attributes(writer)?;
+            must_update_ranked = true;
+        },
+        UpdateState::Nothing => (),
+    }
+
+    // the ranked map must contain the fields of the custom ranking rules
+    // along with the ones the search queries are allowed to sort on
+    if must_update_ranked {
+        let ranking_rules = index.main.ranking_rules(writer)?.unwrap_or_default();
+        let sortable_attributes = index.main.sortable_attributes(writer)?.unwrap_or_default();
+        let ranked_field = ranking_rules
+            .iter()
+            .filter_map(RankingRule::field)
+            .chain(sortable_attributes.iter().map(String::as_str));
+        schema.update_ranked(ranked_field)?;
+        must_reindex = true;
+    }
+
+    match settings.query_rules {
+        UpdateState::Update(v) => {
+            index.main.put_query_rules(writer, &v)?;
+        },
+        UpdateState::Clear => {
+            index.main.delete_query_rules(writer)?;
+        },
+        UpdateState::Nothing => (),
+    }
+
+    match settings.typo_tolerance {
+        UpdateState::Update(v) => {
+            index.main.put_typo_tolerance(writer, &normalize_typo_tolerance(v))?;
+        },
+        UpdateState::Clear => {
+            index.main.delete_typo_tolerance(writer)?;
         },
         UpdateState::Nothing => (),
     }
@@ -311,3 +356,26 @@ pub fn apply_synonyms_update(
 
     Ok(())
 }
+
+/// Normalizes the words typos are disabled on like the words of the queries,
+/// so that the query words can be looked up as they are.
+fn normalize_typo_tolerance(mut typo_tolerance: TypoTolerance) -> TypoTolerance {
+    let no_stop_words = fst::Set::<Vec<u8>>::default();
+    let analyzer = Analyzer::new(AnalyzerConfig::default_with_stopwords(&no_stop_words));
+
+    typo_tolerance.disable_on_words = typo_tolerance
+        .disable_on_words
+        .iter()
+        .flat_map(|word| {
+            let analyzed = analyzer.analyze(word);
+            let words: Vec<_> = analyzed
+                .tokens()
+                .filter(|t| t.is_word())
+                .map(|t| t.word.to_string())
+                .collect();
+            words
+        })
+        .collect();
+
+    typo_tolerance
+}
@@ -0,0 +1,59 @@
+use serde_json::Value;
+
+/// The reserved attribute holding the embedding of a document, an array of numbers.
+pub const VECTOR_FIELD: &str = "_vector";
+
+/// Reads the embedding stored in the `_vector` attribute of a document.
+pub fn vector_from_value(value: &Value) -> Option<Vec<f32>> {
+    let array = value.as_array()?;
+    if array.is_empty() {
+        return None;
+    }
+
+    array.iter().map(|v| v.as_f64().map(|f| f as f32)).collect()
+}
+
+/// Returns the cosine similarity of two vectors, between -1 and 1, or `None` if their
+/// dimensions differ or one of them has a null norm.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> Option<f32> {
+    if a.len() != b.len() {
+        return None;
+    }
+
+    let (mut dot, mut norm_a, mut norm_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return None;
+    }
+
+    Some((dot / (norm_a.sqrt() * norm_b.sqrt())).max(-1.0).min(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn similarity() {
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]), Some(1.0));
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]), Some(0.0));
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[-1.0, 0.0]), Some(-1.0));
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[1.0, 0.0, 0.0]), None);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), None);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(vector_from_value(&json!([1, 0.5, -2])), Some(vec![1.0, 0.5, -2.0]));
+        assert_eq!(vector_from_value(&json!([1, "2"])), None);
+        assert_eq!(vector_from_value(&json!([])), None);
+        assert_eq!(vector_from_value(&json!({ "x": 1 })), None);
+    }
+}
@@ -2,6 +2,7 @@ use std::error::Error;
 use std::ops::Deref;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use meilisearch_core::{Database, DatabaseOptions, Index};
 use sha2::Digest;
@@ -10,6 +11,8 @@ use crate::error::{Error as MSError, ResponseError};
 use crate::index_update_callback;
 use crate::option::Opt;
 use crate::dump::DumpInfo;
+use crate::search_analytics::{SearchAnalytics, DEFAULT_MAX_SEARCHES};
+use crate::search_cache::SearchCache;
 
 #[derive(Clone)]
 pub struct Data {
@@ -33,7 +36,10 @@ pub struct DataInner {
     pub api_keys: ApiKeys,
     pub server_pid: u32,
     pub http_payload_size_limit: usize,
+    pub search_timeout: Option<Duration>,
     pub current_dump: Arc<Mutex<Option<DumpInfo>>>,
+    pub search_analytics: Option<Arc<SearchAnalytics>>,
+    pub search_cache: Option<Arc<SearchCache>>,
 }
 
 #[derive(Clone)]
@@ -73,6 +79,7 @@ impl Data {
         };
 
         let http_payload_size_limit = opt.http_payload_size_limit;
+        let search_timeout = opt.search_timeout_ms.map(Duration::from_millis);
 
         let db = Arc::new(Database::open_or_create(opt.db_path, db_opt)?);
 
@@ -86,6 +93,20 @@ impl Data {
 
         let current_dump = Arc::new(Mutex::new(None));
 
+        let search_analytics = if opt.no_search_analytics {
+            None
+        } else {
+            let max_searches = opt.search_analytics_max_searches.unwrap_or(DEFAULT_MAX_SEARCHES);
+            let search_analytics = SearchAnalytics::new(max_searches);
+            search_analytics.load(&db)?;
+            Some(Arc::new(search_analytics))
+        };
+
+        let search_cache = match opt.search_cache_size {
+            0 => None,
+            size => Some(Arc::new(SearchCache::new(size))),
+        };
+
         let inner_data = DataInner {
             db: db.clone(),
             db_path,
@@ -94,7 +115,10 @@ impl Data {
             api_keys,
             server_pid,
             http_payload_size_limit,
+            search_timeout,
             current_dump,
+            search_analytics,
+            search_cache,
         };
 
         let data = Data {
@@ -274,10 +274,10 @@ fn dump_index_documents(data: &web::Data<Data>, reader: &MainReader, dir_path: &
 
     let mut offset = 0;
     loop {
-        let documents = crate::routes::document::get_all_documents_sync(data, reader, index_uid, offset, dump_batch_size, None)?;
+        let documents = crate::routes::document::get_all_documents_sync(data, reader, index_uid, offset, dump_batch_size, None, None)?;
         if documents.is_empty() { break; } else { offset += dump_batch_size; }
 
-        for document in documents {
+        for (_, document) in documents {
             serde_json::to_writer(&file, &document)?;
             writeln!(&file)?;
         }
@@ -1,16 +1,16 @@
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use indexmap::IndexMap;
 use log::error;
 use meilisearch_core::{Filter, MainReader};
 use meilisearch_core::facets::FacetFilter;
 use meilisearch_core::criterion::*;
-use meilisearch_core::settings::RankingRule;
-use meilisearch_core::{Highlight, Index, RankedMap};
-use meilisearch_schema::{FieldId, Schema};
+use meilisearch_core::settings::{RankingRule, DEFAULT_RANKING_RULES};
+use meilisearch_core::{Cursor, DocumentId, Highlight, Index, MatchingStrategy, NbHits, Number, RankedMap};
+use meilisearch_schema::{FieldId, IndexedPos, Schema};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use siphasher::sip::SipHasher;
@@ -36,6 +36,26 @@ impl IndexSearchExt for Index {
             matches: false,
             facet_filters: None,
             facets: None,
+            sort: None,
+            ranking_rules: None,
+            distinct: None,
+            distinct_count: 1,
+            ranking_infos: false,
+            highlight_pre_tag: "<em>".to_string(),
+            highlight_post_tag: "</em>".to_string(),
+            crop_marker: String::new(),
+            crop_snippets: 1,
+            attributes_to_search_on: None,
+            timeout: None,
+            cursor: None,
+            nb_hits: NbHits::default(),
+            matching_strategy: MatchingStrategy::default(),
+            exact_words: false,
+            auto_correct: false,
+            excluded_documents: Vec::new(),
+            pinned_documents: Vec::new(),
+            vector: None,
+            semantic_ratio: None,
         }
     }
 }
@@ -51,7 +71,27 @@ pub struct SearchBuilder<'a> {
     filters: Option<String>,
     matches: bool,
     facet_filters: Option<FacetFilter>,
-    facets: Option<Vec<(FieldId, String)>>
+    facets: Option<Vec<(FieldId, String)>>,
+    sort: Option<Vec<RankingRule>>,
+    ranking_rules: Option<Vec<RankingRule>>,
+    distinct: Option<FieldId>,
+    distinct_count: usize,
+    ranking_infos: bool,
+    highlight_pre_tag: String,
+    highlight_post_tag: String,
+    crop_marker: String,
+    crop_snippets: usize,
+    attributes_to_search_on: Option<Vec<IndexedPos>>,
+    timeout: Option<Duration>,
+    cursor: Option<Cursor>,
+    nb_hits: NbHits,
+    matching_strategy: MatchingStrategy,
+    exact_words: bool,
+    auto_correct: bool,
+    excluded_documents: Vec<DocumentId>,
+    pinned_documents: Vec<(usize, DocumentId)>,
+    vector: Option<Vec<f32>>,
+    semantic_ratio: Option<f32>,
 }
 
 impl<'a> SearchBuilder<'a> {
@@ -106,7 +146,184 @@ impl<'a> SearchBuilder<'a> {
         self
     }
 
-    pub fn search(self, reader: &MainReader) -> Result<SearchResult, ResponseError> {
+    pub fn sort(&mut self, value: Vec<RankingRule>) -> &SearchBuilder {
+        self.sort = Some(value);
+        self
+    }
+
+    pub fn ranking_rules(&mut self, value: Vec<RankingRule>) -> &SearchBuilder {
+        self.ranking_rules = Some(value);
+        self
+    }
+
+    pub fn distinct(&mut self, value: FieldId) -> &SearchBuilder {
+        self.distinct = Some(value);
+        self
+    }
+
+    pub fn distinct_count(&mut self, value: usize) -> &SearchBuilder {
+        self.distinct_count = value;
+        self
+    }
+
+    pub fn get_ranking_infos(&mut self) -> &SearchBuilder {
+        self.ranking_infos = true;
+        self
+    }
+
+    pub fn highlight_pre_tag(&mut self, value: String) -> &SearchBuilder {
+        self.highlight_pre_tag = value;
+        self
+    }
+
+    pub fn highlight_post_tag(&mut self, value: String) -> &SearchBuilder {
+        self.highlight_post_tag = value;
+        self
+    }
+
+    pub fn crop_marker(&mut self, value: String) -> &SearchBuilder {
+        self.crop_marker = value;
+        self
+    }
+
+    pub fn crop_snippets(&mut self, value: usize) -> &SearchBuilder {
+        self.crop_snippets = value;
+        self
+    }
+
+    pub fn attributes_to_search_on(&mut self, value: Vec<IndexedPos>) -> &SearchBuilder {
+        self.attributes_to_search_on = Some(value);
+        self
+    }
+
+    pub fn timeout(&mut self, value: Duration) -> &SearchBuilder {
+        self.timeout = Some(value);
+        self
+    }
+
+    pub fn cursor(&mut self, value: Cursor) -> &SearchBuilder {
+        self.cursor = Some(value);
+        self
+    }
+
+    pub fn nb_hits(&mut self, value: NbHits) -> &SearchBuilder {
+        self.nb_hits = value;
+        self
+    }
+
+    pub fn matching_strategy(&mut self, value: MatchingStrategy) -> &SearchBuilder {
+        self.matching_strategy = value;
+        self
+    }
+
+    pub fn exact_words(&mut self, value: bool) -> &SearchBuilder {
+        self.exact_words = value;
+        self
+    }
+
+    pub fn auto_correct(&mut self, value: bool) -> &SearchBuilder {
+        self.auto_correct = value;
+        self
+    }
+
+    pub fn exclude_document(&mut self, value: DocumentId) -> &SearchBuilder {
+        self.excluded_documents.push(value);
+        self
+    }
+
+    pub fn vector(&mut self, value: Vec<f32>) -> &SearchBuilder {
+        self.vector = Some(value);
+        self
+    }
+
+    pub fn semantic_ratio(&mut self, value: f32) -> &SearchBuilder {
+        self.semantic_ratio = Some(value);
+        self
+    }
+
+    pub fn search(mut self, reader: &MainReader) -> Result<SearchResult, ResponseError> {
+        let original_query = self.query.clone();
+        self.apply_query_rules(reader)?;
+
+        // the pinned documents of a page depend on its position, not on the previous page
+        if self.cursor.is_some() && !self.pinned_documents.is_empty() {
+            let message = "a cursor can't be used on a search that pins documents";
+            return Err(Error::bad_parameter("cursor", message).into());
+        }
+
+        let mut results = self.search_query(reader, self.query.as_deref())?;
+
+        // a correction is only looked for, and run in place of the query, when it gave nothing
+        if let (Some(query), 0) = (&self.query, results.nb_hits) {
+            let suggested_query = self.index.correct_query(reader, query)?;
+
+            if let (Some(suggested), true) = (&suggested_query, self.auto_correct) {
+                let processing_time_ms = results.processing_time_ms;
+                results = self.search_query(reader, Some(suggested))?;
+                results.processing_time_ms += processing_time_ms;
+                results.query = query.clone();
+                results.query_corrected = true;
+            }
+
+            results.suggested_query = suggested_query;
+        }
+
+        // the results echo the query given, even when a rule rewrote it
+        if let Some(query) = original_query {
+            results.query = query;
+        }
+
+        Ok(results)
+    }
+
+    /// Applies the query rules of the index matching this search. The query is rewritten,
+    /// the filters are extended and the hidden documents are excluded before the documents
+    /// are sorted, the pinned documents are then inserted in the sorted ones.
+    fn apply_query_rules(&mut self, reader: &MainReader) -> Result<(), ResponseError> {
+        let rules = match self.index.main.query_rules(reader)? {
+            Some(rules) => rules,
+            None => return Ok(()),
+        };
+
+        let query = self.query.clone();
+        let filters = self.filters.clone();
+        for rule in rules.iter().filter(|rule| rule.matches(query.as_deref(), filters.as_deref())) {
+            let consequences = &rule.consequences;
+
+            if let Some(rewritten) = &consequences.rewrite_query {
+                self.query = Some(rewritten.clone());
+            }
+
+            if let Some(filter) = &consequences.filters {
+                self.filters = Some(match self.filters.take() {
+                    Some(filters) => format!("({}) AND ({})", filters, filter),
+                    None => filter.clone(),
+                });
+            }
+
+            // the ids of documents that do not exist are ignored
+            for id in &consequences.hide {
+                if let Some(id) = self.index.main.external_to_internal_docid(reader, id)? {
+                    self.excluded_documents.push(id);
+                }
+            }
+
+            for pinned in &consequences.pin {
+                if let Some(id) = self.index.main.external_to_internal_docid(reader, &pinned.id)? {
+                    self.pinned_documents.push((pinned.position, id));
+                }
+            }
+        }
+
+        // a hidden document is never pinned, and a document is only pinned once
+        let excluded_documents = &self.excluded_documents;
+        let mut seen = HashSet::new();
+        self.pinned_documents.retain(|(_, id)| !excluded_documents.contains(id) && seen.insert(*id));
+
+        Ok(())
+    }
+
+    fn search_query(&self, reader: &MainReader, query: Option<&str>) -> Result<SearchResult, ResponseError> {
         let schema = self
             .index
             .main
@@ -121,12 +338,24 @@ impl<'a> SearchBuilder<'a> {
             None => self.index.query_builder(),
         };
 
-        if let Some(filter_expression) = &self.filters {
-            let filter = Filter::parse(filter_expression, &schema)?;
+        let filter = match &self.filters {
+            Some(filter_expression) => Some(Filter::parse(filter_expression, &schema)?),
+            None => None,
+        };
+
+        let excluded_documents: HashSet<_> = self.excluded_documents.iter().copied().collect();
+        if filter.is_some() || !excluded_documents.is_empty() {
             let index = &self.index;
             query_builder.with_filter(move |id| {
+                if excluded_documents.contains(&id) {
+                    return false;
+                }
+
                 let reader = &reader;
-                let filter = &filter;
+                let filter = match &filter {
+                    Some(filter) => filter,
+                    None => return true,
+                };
                 match filter.test(reader, index, id) {
                     Ok(res) => res,
                     Err(e) => {
@@ -137,9 +366,15 @@ impl<'a> SearchBuilder<'a> {
             });
         }
 
-        if let Some(field) = self.index.main.distinct_attribute(reader)? {
+        // the distinct attribute of the query replaces the one of the index
+        let distinct = match self.distinct {
+            Some(field) => Some(field),
+            None => self.index.main.distinct_attribute(reader)?,
+        };
+
+        if let Some(field) = distinct {
             let index = &self.index;
-            query_builder.with_distinct(1, move |id| {
+            query_builder.with_distinct(self.distinct_count, move |id| {
                 match index.document_attribute_bytes(reader, id, field) {
                     Ok(Some(bytes)) => {
                         let mut s = SipHasher::new();
@@ -151,11 +386,32 @@ impl<'a> SearchBuilder<'a> {
             });
         }
 
-        query_builder.set_facet_filter(self.facet_filters);
-        query_builder.set_facets(self.facets);
+        query_builder.set_facet_filter(self.facet_filters.clone());
+        query_builder.set_facets(self.facets.clone());
+        query_builder.set_sort(self.placeholder_sort());
+        query_builder.set_ranking_infos(self.ranking_infos);
+        query_builder.set_cursor(self.cursor.clone());
+        query_builder.set_nb_hits(self.nb_hits);
+        query_builder.set_matching_strategy(self.matching_strategy);
+        query_builder.set_exact_words(self.exact_words);
+        query_builder.set_vector(self.vector.clone());
+        query_builder.set_pinned_documents(self.pinned_documents.clone());
+        if let Some(semantic_ratio) = self.semantic_ratio {
+            query_builder.set_semantic_ratio(semantic_ratio);
+        }
+
+        if let Some(attributes) = &self.attributes_to_search_on {
+            for attribute in attributes {
+                query_builder.add_searchable_attribute(attribute.0);
+            }
+        }
+
+        if let Some(timeout) = self.timeout {
+            query_builder.with_fetch_timeout(timeout);
+        }
 
         let start = Instant::now();
-        let result = query_builder.query(reader, self.query.as_deref(), self.offset..(self.offset + self.limit));
+        let result = query_builder.query(reader, query, self.offset..(self.offset + self.limit));
         let search_result = result.map_err(Error::search_documents)?;
         let time_ms = start.elapsed().as_millis() as usize;
 
@@ -186,6 +442,15 @@ impl<'a> SearchBuilder<'a> {
             },
         }
 
+        let ranking_rules = match (&self.sort, &self.ranking_rules) {
+            (Some(rules), _) | (None, Some(rules)) => Some(rules.clone()),
+            (None, None) => self.index.main.ranking_rules(reader)?,
+        };
+        let geo_point = ranking_rules.iter().flatten().find_map(|rule| match rule {
+            RankingRule::GeoDistance(point) => Some(*point),
+            _ => None,
+        });
+
         let mut hits = Vec::with_capacity(self.limit);
         for doc in search_result.documents {
             let mut document: IndexMap<String, Value> = self
@@ -203,7 +468,7 @@ impl<'a> SearchBuilder<'a> {
 
             // Crops fields if needed
             if let Some(fields) = &self.attributes_to_crop {
-                crop_document(&mut formatted, &mut matches, &schema, fields);
+                crop_document(&mut formatted, &mut matches, &schema, fields, &self.crop_marker, self.crop_snippets);
             }
 
             // Transform to readable matches
@@ -213,7 +478,13 @@ impl<'a> SearchBuilder<'a> {
                     self.attributes_to_highlight.clone(),
                     &schema,
                 );
-                formatted = calculate_highlights(&formatted, &matches, attributes_to_highlight);
+                formatted = calculate_highlights(
+                    &formatted,
+                    &matches,
+                    attributes_to_highlight,
+                    &self.highlight_pre_tag,
+                    &self.highlight_post_tag,
+                );
             }
 
             let matches_info = if self.matches {
@@ -226,10 +497,24 @@ impl<'a> SearchBuilder<'a> {
                 document.retain(|key, _| attributes_to_retrieve.contains(&key.to_string()))
             }
 
+            let ranking_info = if self.ranking_infos {
+                Some(calculate_ranking_infos(&doc.ranking_infos))
+            } else {
+                None
+            };
+
+            // the distance, in meters, to the point the documents are sorted around
+            let geo_distance = geo_point.and_then(|point| {
+                let location = ranked_map.geo_point(doc.id)?;
+                Some(location.distance(&point).round() as u64)
+            });
+
             let hit = SearchHit {
                 document,
                 formatted,
                 matches_info,
+                ranking_info,
+                geo_distance,
             };
 
             hits.push(hit);
@@ -241,8 +526,12 @@ impl<'a> SearchBuilder<'a> {
             limit: self.limit,
             nb_hits: search_result.nb_hits,
             exhaustive_nb_hits: search_result.exhaustive_nb_hit,
+            partial: search_result.partial,
+            next_cursor: search_result.cursor.as_ref().map(encode_cursor),
             processing_time_ms: time_ms,
-            query: self.query.unwrap_or_default(),
+            query: query.unwrap_or_default().to_string(),
+            suggested_query: None,
+            query_corrected: false,
             facets_distribution: search_result.facets,
             exhaustive_facets_count: search_result.exhaustive_facets_count,
         };
@@ -250,43 +539,77 @@ impl<'a> SearchBuilder<'a> {
         Ok(results)
     }
 
+    /// The custom rules sorting a placeholder search in place of the ones of the index,
+    /// either the sort of the query or the custom rules of the ranking rules given with it.
+    fn placeholder_sort(&self) -> Option<Vec<RankingRule>> {
+        match (&self.sort, &self.ranking_rules) {
+            (Some(sort), _) => Some(sort.clone()),
+            (None, Some(ranking_rules)) => Some(ranking_rules.iter().filter(|r| r.is_custom()).cloned().collect()),
+            (None, None) => None,
+        }
+    }
+
     pub fn get_criteria(
         &self,
         reader: &MainReader,
         ranked_map: &'a RankedMap,
         schema: &Schema,
     ) -> Result<Option<Criteria<'a>>, ResponseError> {
-        let ranking_rules = self.index.main.ranking_rules(reader)?;
-
-        if let Some(ranking_rules) = ranking_rules {
-            let mut builder = CriteriaBuilder::with_capacity(7 + ranking_rules.len());
-            for rule in ranking_rules {
-                match rule {
-                    RankingRule::Typo => builder.push(Typo),
-                    RankingRule::Words => builder.push(Words),
-                    RankingRule::Proximity => builder.push(Proximity),
-                    RankingRule::Attribute => builder.push(Attribute),
-                    RankingRule::WordsPosition => builder.push(WordsPosition),
-                    RankingRule::Exactness => builder.push(Exactness),
-                    RankingRule::Asc(field) => {
-                        match SortByAttr::lower_is_better(&ranked_map, &schema, &field) {
-                            Ok(rule) => builder.push(rule),
-                            Err(err) => error!("Error during criteria builder; {:?}", err),
-                        }
+        // the ranking rules given with the query replace the ones of the index
+        let index_ranking_rules = match &self.ranking_rules {
+            Some(ranking_rules) => Some(ranking_rules.clone()),
+            None => self.index.main.ranking_rules(reader)?,
+        };
+
+        let ranking_rules = match (index_ranking_rules, &self.sort) {
+            (Some(ranking_rules), _) => ranking_rules,
+            (None, Some(_)) => DEFAULT_RANKING_RULES.to_vec(),
+            (None, None) => return Ok(None),
+        };
+
+        // The sort of the query replaces the custom rules of the index, it takes the place
+        // of the first one or comes after the other rules if the index doesn't have any.
+        let ranking_rules = match &self.sort {
+            Some(sort) => {
+                let is_custom = |rule: &RankingRule| rule.is_custom();
+                let position = ranking_rules.iter().position(is_custom).unwrap_or_else(|| ranking_rules.len());
+                let (before, after) = ranking_rules.split_at(position);
+                before
+                    .iter()
+                    .chain(sort)
+                    .chain(after.iter().filter(|r| !is_custom(*r)))
+                    .cloned()
+                    .collect()
+            }
+            None => ranking_rules,
+        };
+
+        let mut builder = CriteriaBuilder::with_capacity(7 + ranking_rules.len());
+        for rule in ranking_rules {
+            match rule {
+                RankingRule::Typo => builder.push(Typo),
+                RankingRule::Words => builder.push(Words),
+                RankingRule::Proximity => builder.push(Proximity),
+                RankingRule::Attribute => builder.push(Attribute),
+                RankingRule::WordsPosition => builder.push(WordsPosition),
+                RankingRule::Exactness => builder.push(Exactness),
+                RankingRule::Asc(field) => {
+                    match SortByAttr::lower_is_better(&ranked_map, &schema, &field) {
+                        Ok(rule) => builder.push(rule),
+                        Err(err) => error!("Error during criteria builder; {:?}", err),
                     }
-                    RankingRule::Desc(field) => {
-                        match SortByAttr::higher_is_better(&ranked_map, &schema, &field) {
-                            Ok(rule) => builder.push(rule),
-                            Err(err) => error!("Error during criteria builder; {:?}", err),
-                        }
+                }
+                RankingRule::Desc(field) => {
+                    match SortByAttr::higher_is_better(&ranked_map, &schema, &field) {
+                        Ok(rule) => builder.push(rule),
+                        Err(err) => error!("Error during criteria builder; {:?}", err),
                     }
                 }
+                RankingRule::GeoDistance(point) => builder.push(GeoDistance::new(&ranked_map, point)),
             }
-            builder.push(DocumentId);
-            return Ok(Some(builder.build()));
         }
-
-        Ok(None)
+        builder.push(DocumentId);
+        Ok(Some(builder.build()))
     }
 }
 
@@ -313,7 +636,7 @@ impl Ord for MatchPosition {
 
 pub type HighlightInfos = HashMap<String, Value>;
 pub type MatchesInfos = HashMap<String, Vec<MatchPosition>>;
-// pub type RankingInfos = HashMap<String, u64>;
+pub type RankingInfos = IndexMap<String, Value>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchHit {
@@ -323,6 +646,10 @@ pub struct SearchHit {
     pub formatted: IndexMap<String, Value>,
     #[serde(rename = "_matchesInfo", skip_serializing_if = "Option::is_none")]
     pub matches_info: Option<MatchesInfos>,
+    #[serde(rename = "_rankingInfo", skip_serializing_if = "Option::is_none")]
+    pub ranking_info: Option<RankingInfos>,
+    #[serde(rename = "_geoDistance", skip_serializing_if = "Option::is_none")]
+    pub geo_distance: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -333,14 +660,48 @@ pub struct SearchResult {
     pub limit: usize,
     pub nb_hits: usize,
     pub exhaustive_nb_hits: bool,
+    pub partial: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
     pub processing_time_ms: usize,
     pub query: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggested_query: Option<String>,
+    #[serde(skip_serializing_if = "is_false")]
+    pub query_corrected: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub facets_distribution: Option<HashMap<String, HashMap<String, usize>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub exhaustive_facets_count: Option<bool>,
 }
 
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// Encodes a cursor into the opaque string returned to the clients.
+pub fn encode_cursor(cursor: &Cursor) -> String {
+    let bytes = serde_json::to_vec(cursor).unwrap_or_default();
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decodes a cursor previously returned by `encode_cursor`.
+pub fn decode_cursor(cursor: &str) -> Result<Cursor, Error> {
+    let invalid = || Error::bad_parameter("cursor", "the cursor is invalid");
+
+    if cursor.len() % 2 != 0 || !cursor.is_ascii() {
+        return Err(invalid());
+    }
+
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+
+    serde_json::from_slice(&bytes).map_err(|_| invalid())
+}
+
 /// returns the start index and the length on the crop.
 fn aligned_crop(text: &str, match_index: usize, context: usize) -> (usize, usize) {
     let is_word_component = |c: &char| c.is_alphanumeric() && !super::is_cjk(*c);
@@ -369,35 +730,124 @@ fn aligned_crop(text: &str, match_index: usize, context: usize) -> (usize, usize
     (start, end - start)
 }
 
+/// Returns the crop windows, as the start index and the length of each window, that contain
+/// the most distinct matched words, the windows where these words are the closest first.
+/// The distinct matched words are the distinct lowercased texts of the matches.
+/// At most `snippets` windows that don't overlap are returned, ordered by position.
+fn best_crop_windows(
+    text: &str,
+    matches: &[Highlight],
+    context: usize,
+    snippets: usize,
+) -> Vec<(usize, usize)> {
+    // only keep the longest of the matches starting at the same position
+    let matches: Vec<_> = matches
+        .linear_group_by_key(|m| m.char_index)
+        .map(|group| *group.last().unwrap())
+        .collect();
+
+    let match_end = |m: &Highlight| m.char_index as usize + m.char_length as usize;
+    let matched_word = |m: &Highlight| text.get(m.char_index as usize..match_end(m)).map(str::to_lowercase);
+
+    // a candidate window starts at each match and spans over the following
+    // matches that fit in the crop length
+    let mut candidates = Vec::with_capacity(matches.len());
+    for (i, first) in matches.iter().enumerate() {
+        let start = first.char_index as usize;
+        let group_len = 1 + matches[i + 1..]
+            .iter()
+            .take_while(|m| match_end(m) <= start + 2 * context)
+            .count();
+        let group = &matches[i..i + group_len];
+
+        // the window spans until the last distinct word is seen
+        let mut words = HashSet::new();
+        let mut end = match_end(first);
+        for m in group {
+            if let Some(word) = matched_word(m) {
+                if words.insert(word) {
+                    end = match_end(m);
+                }
+            }
+        }
+
+        let center = if words.len() <= 1 { start } else { (start + end) / 2 };
+        candidates.push((words.len(), end - start, center));
+    }
+
+    // the sort is stable, the first windows are preferred on equality
+    candidates.sort_by(|(aw, aspan, _), (bw, bspan, _)| bw.cmp(aw).then(aspan.cmp(bspan)));
+
+    let mut windows: Vec<(usize, usize)> = Vec::with_capacity(snippets);
+    for (_, _, center) in candidates {
+        if windows.len() >= snippets {
+            break;
+        }
+
+        let (start, count) = aligned_crop(text, center, context);
+        let overlaps = windows.iter().any(|&(s, c)| start < s + c && s < start + count);
+        if !overlaps {
+            windows.push((start, count));
+        }
+    }
+
+    windows.sort_unstable();
+    windows
+}
+
 fn crop_text(
     text: &str,
     matches: impl IntoIterator<Item = Highlight>,
     context: usize,
+    crop_marker: &str,
+    snippets: usize,
 ) -> (String, Vec<Highlight>) {
-    let mut matches = matches.into_iter().peekable();
-
-    let char_index = matches.peek().map(|m| m.char_index as usize).unwrap_or(0);
-    let (start, count) = aligned_crop(text, char_index, context);
-
-    // TODO do something about double allocation
-    let text = text
-        .chars()
-        .skip(start)
-        .take(count)
-        .collect::<String>()
-        .trim()
-        .to_string();
-
-    // update matches index to match the new cropped text
-    let matches = matches
-        .take_while(|m| (m.char_index as usize) + (m.char_length as usize) <= start + count)
-        .map(|m| Highlight {
-            char_index: m.char_index - start as u16,
-            ..m
-        })
-        .collect();
+    let matches: Vec<_> = matches.into_iter().collect();
+
+    let mut windows = best_crop_windows(text, &matches, context, snippets.max(1));
+    if windows.is_empty() {
+        windows.push(aligned_crop(text, 0, context));
+    }
+
+    let text_len = text.chars().count();
+    let mut cropped_text = String::new();
+    let mut cropped_matches = Vec::new();
+
+    for (i, &(start, count)) in windows.iter().enumerate() {
+        // the marker is only added where the text was cut and between snippets
+        if i > 0 || start > 0 {
+            cropped_text.push_str(crop_marker);
+        }
 
-    (text, matches)
+        let offset = cropped_text.len();
+
+        // TODO do something about double allocation
+        let snippet = text
+            .chars()
+            .skip(start)
+            .take(count)
+            .collect::<String>();
+        cropped_text.push_str(snippet.trim());
+
+        // update matches index to match the new cropped text
+        let window_matches = matches
+            .iter()
+            .filter(|m| (m.char_index as usize) >= start)
+            .filter(|m| (m.char_index as usize) + (m.char_length as usize) <= start + count)
+            .map(|m| Highlight {
+                char_index: m.char_index - start as u16 + offset as u16,
+                ..*m
+            });
+        cropped_matches.extend(window_matches);
+    }
+
+    if let Some(&(start, count)) = windows.last() {
+        if start + count < text_len {
+            cropped_text.push_str(crop_marker);
+        }
+    }
+
+    (cropped_text, cropped_matches)
 }
 
 fn crop_document(
@@ -405,6 +855,8 @@ fn crop_document(
     matches: &mut Vec<Highlight>,
     schema: &Schema,
     fields: &HashMap<String, usize>,
+    crop_marker: &str,
+    snippets: usize,
 ) {
     matches.sort_unstable_by_key(|m| (m.char_index, m.char_length));
 
@@ -414,21 +866,118 @@ fn crop_document(
             None => continue,
         };
 
-        let selected_matches = matches
+        let selected_matches: Vec<_> = matches
             .iter()
             .filter(|m| FieldId::new(m.attribute) == attribute)
-            .cloned();
+            .map(|m| MatchPosition { start: m.char_index as usize, length: m.char_length as usize })
+            .collect();
+
+        if let Some(value) = document.get_mut(field) {
+            let cropped_matches = map_string_leaves(value, &selected_matches, &mut |text, positions| {
+                let leaf_matches = positions.iter().map(|m| Highlight {
+                    attribute: attribute.0,
+                    char_index: m.start as u16,
+                    char_length: m.length as u16,
+                });
 
-        if let Some(Value::String(ref mut original_text)) = document.get_mut(field) {
-            let (cropped_text, cropped_matches) =
-                crop_text(original_text, selected_matches, *length);
+                let (cropped_text, cropped_matches) = crop_text(text, leaf_matches, *length, crop_marker, snippets);
+                *text = cropped_text;
 
-            *original_text = cropped_text;
+                cropped_matches
+                    .into_iter()
+                    .map(|m| MatchPosition { start: m.char_index as usize, length: m.char_length as usize })
+                    .collect()
+            });
 
             matches.retain(|m| FieldId::new(m.attribute) != attribute);
-            matches.extend_from_slice(&cropped_matches);
+            matches.extend(cropped_matches.into_iter().map(|m| Highlight {
+                attribute: attribute.0,
+                char_index: m.start as u16,
+                char_length: m.length as u16,
+            }));
+        }
+    }
+}
+
+/// Calls `f` on every string contained in the value, arrays and objects included, with
+/// the matches that are in this string. The positions of the matches are relative to the
+/// string given to `f` and must be returned relative to the string once updated by `f`.
+///
+/// Arrays and objects are indexed as one text built by `value_to_string`, the matches of
+/// such values are positioned in this text. The strings are visited in the same order and
+/// the returned matches are positioned in the text of the updated value.
+fn map_string_leaves<F>(value: &mut Value, matches: &[MatchPosition], f: &mut F) -> Vec<MatchPosition>
+where
+    F: FnMut(&mut String, Vec<MatchPosition>) -> Vec<MatchPosition>,
+{
+    struct Offsets {
+        original: usize,
+        updated: usize,
+    }
+
+    impl Offsets {
+        fn skip(&mut self, len: usize) {
+            self.original += len;
+            self.updated += len;
         }
     }
+
+    // the separator written by value_to_string after array values and object keys and values
+    const SEPARATOR_LEN: usize = ". ".len();
+
+    fn walk<F>(
+        value: &mut Value,
+        matches: &[MatchPosition],
+        offsets: &mut Offsets,
+        f: &mut F,
+        result: &mut Vec<MatchPosition>,
+    )
+    where
+        F: FnMut(&mut String, Vec<MatchPosition>) -> Vec<MatchPosition>,
+    {
+        match value {
+            Value::Null => (),
+            Value::Bool(boolean) => offsets.skip(boolean.to_string().len()),
+            Value::Number(number) => offsets.skip(number.to_string().len()),
+            Value::String(text) => {
+                let start = offsets.original;
+                let end = start + text.len();
+
+                let leaf_matches = matches
+                    .iter()
+                    .filter(|m| m.start >= start && m.start + m.length <= end)
+                    .map(|m| MatchPosition { start: m.start - start, length: m.length })
+                    .collect();
+
+                let updated_matches = f(text, leaf_matches);
+                result.extend(updated_matches.into_iter().map(|m| MatchPosition {
+                    start: m.start + offsets.updated,
+                    length: m.length,
+                }));
+
+                offsets.original = end;
+                offsets.updated += text.len();
+            }
+            Value::Array(array) => {
+                for value in array {
+                    walk(value, matches, offsets, f, result);
+                    offsets.skip(SEPARATOR_LEN);
+                }
+            }
+            Value::Object(object) => {
+                for (key, value) in object {
+                    offsets.skip(key.len() + SEPARATOR_LEN);
+                    walk(value, matches, offsets, f, result);
+                    offsets.skip(SEPARATOR_LEN);
+                }
+            }
+        }
+    }
+
+    let mut offsets = Offsets { original: 0, updated: 0 };
+    let mut result = Vec::new();
+    walk(value, matches, &mut offsets, f, &mut result);
+    result
 }
 
 fn calculate_matches(
@@ -473,42 +1022,65 @@ fn calculate_highlights(
     document: &IndexMap<String, Value>,
     matches: &MatchesInfos,
     attributes_to_highlight: &HashSet<String>,
+    pre_tag: &str,
+    post_tag: &str,
 ) -> IndexMap<String, Value> {
     let mut highlight_result = document.clone();
 
     for (attribute, matches) in matches.iter() {
         if attributes_to_highlight.contains(attribute) {
-            if let Some(Value::String(value)) = document.get(attribute) {
-                let value = value;
-                let mut highlighted_value = String::new();
-                let mut index = 0;
-
-                let longest_matches = matches
-                    .linear_group_by_key(|m| m.start)
-                    .map(|group| group.last().unwrap())
-                    .filter(move |m| m.start >= index);
-
-                for m in longest_matches {
-                    let before = value.get(index..m.start);
-                    let highlighted = value.get(m.start..(m.start + m.length));
-                    if let (Some(before), Some(highlighted)) = (before, highlighted) {
-                        highlighted_value.push_str(before);
-                        highlighted_value.push_str("<em>");
-                        highlighted_value.push_str(highlighted);
-                        highlighted_value.push_str("</em>");
-                        index = m.start + m.length;
-                    } else {
-                        error!("value: {:?}; index: {:?}, match: {:?}", value, index, m);
-                    }
-                }
-                highlighted_value.push_str(&value[index..]);
-                highlight_result.insert(attribute.to_string(), Value::String(highlighted_value));
-            };
+            if let Some(value) = highlight_result.get_mut(attribute) {
+                map_string_leaves(value, matches, &mut |text, matches| {
+                    *text = highlight_text(text, &matches, pre_tag, post_tag);
+                    Vec::new()
+                });
+            }
         }
     }
     highlight_result
 }
 
+fn highlight_text(value: &str, matches: &[MatchPosition], pre_tag: &str, post_tag: &str) -> String {
+    let mut highlighted_value = String::new();
+    let mut index = 0;
+
+    let longest_matches = matches
+        .linear_group_by_key(|m| m.start)
+        .map(|group| group.last().unwrap())
+        .filter(move |m| m.start >= index);
+
+    for m in longest_matches {
+        let before = value.get(index..m.start);
+        let highlighted = value.get(m.start..(m.start + m.length));
+        if let (Some(before), Some(highlighted)) = (before, highlighted) {
+            highlighted_value.push_str(before);
+            highlighted_value.push_str(pre_tag);
+            highlighted_value.push_str(highlighted);
+            highlighted_value.push_str(post_tag);
+            index = m.start + m.length;
+        } else {
+            error!("value: {:?}; index: {:?}, match: {:?}", value, index, m);
+        }
+    }
+    highlighted_value.push_str(&value[index..]);
+    highlighted_value
+}
+
+fn calculate_ranking_infos(ranking_infos: &[(String, Number)]) -> RankingInfos {
+    ranking_infos
+        .iter()
+        .map(|(name, number)| {
+            let value = match *number {
+                Number::Unsigned(n) => Value::from(n),
+                Number::Signed(n) => Value::from(n),
+                Number::Float(n) => Value::from(n.into_inner()),
+                Number::Null => Value::Null,
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -545,6 +1117,51 @@ mod tests {
         assert_eq!("の", cropped);
     }
 
+    #[test]
+    fn crop_with_marker() {
+        let text = "the quick brown fox jumps over the lazy dog";
+        let matches = vec![Highlight { attribute: 0, char_index: 16, char_length: 3 }];
+
+        let (cropped, matches) = crop_text(text, matches, 5, "…", 1);
+        assert_eq!("…fox jumps…", cropped);
+
+        let m = matches[0];
+        let start = m.char_index as usize;
+        assert_eq!("fox", &cropped[start..start + m.char_length as usize]);
+
+        // nothing was cut at the start of the text
+        let matches = vec![Highlight { attribute: 0, char_index: 0, char_length: 3 }];
+        let (cropped, _) = crop_text(text, matches, 5, "…", 1);
+        assert_eq!("the quick…", cropped);
+    }
+
+    #[test]
+    fn crop_best_window() {
+        let text = "rust is a language. here are some filler words to separate the passages. rust for the web is nice";
+        let matches = vec![
+            Highlight { attribute: 0, char_index: 0, char_length: 4 },
+            Highlight { attribute: 0, char_index: 73, char_length: 4 },
+            Highlight { attribute: 0, char_index: 86, char_length: 3 },
+        ];
+
+        // the window containing both query words is preferred over the first match
+        let (cropped, cropped_matches) = crop_text(text, matches.clone(), 10, "…", 1);
+        assert!(cropped.contains("rust for the web"));
+        assert!(!cropped.contains("language"));
+        assert_eq!(cropped_matches.len(), 2);
+        for m in cropped_matches {
+            let start = m.char_index as usize;
+            let word = &cropped[start..start + m.char_length as usize];
+            assert!(word == "rust" || word == "web");
+        }
+
+        // several snippets are ordered by position and joined by the crop marker
+        let (cropped, cropped_matches) = crop_text(text, matches, 10, "…", 2);
+        assert!(cropped.starts_with("rust is a…"));
+        assert!(cropped.contains("rust for the web"));
+        assert_eq!(cropped_matches.len(), 3);
+    }
+
     #[test]
     fn calculate_matches() {
         let mut matches = Vec::new();
@@ -601,7 +1218,7 @@ mod tests {
             length: 9,
         });
         matches.insert("description".to_string(), m);
-        let result = super::calculate_highlights(&document, &matches, &attributes_to_highlight);
+        let result = super::calculate_highlights(&document, &matches, &attributes_to_highlight, "<em>", "</em>");
 
         let mut result_expected = IndexMap::new();
         result_expected.insert(
@@ -613,6 +1230,61 @@ mod tests {
         assert_eq!(result, result_expected);
     }
 
+    #[test]
+    fn highlight_nested_values() {
+        let data = r#"{
+            "author": { "name": "John", "tags": ["rust", "web"] }
+        }"#;
+
+        let document: IndexMap<String, Value> = serde_json::from_str(data).unwrap();
+        let mut attributes_to_highlight = HashSet::new();
+        attributes_to_highlight.insert("author".to_string());
+
+        // positions in the text indexed for this value: "name. John. tags. rust. web. . "
+        let mut matches = HashMap::new();
+        matches.insert("author".to_string(), vec![
+            MatchPosition { start: 6, length: 4 },
+            MatchPosition { start: 24, length: 3 },
+        ]);
+
+        let result = super::calculate_highlights(&document, &matches, &attributes_to_highlight, "<em>", "</em>");
+
+        let expected = serde_json::json!({ "name": "<em>John</em>", "tags": ["rust", "<em>web</em>"] });
+        assert_eq!(result["author"], expected);
+    }
+
+    #[test]
+    fn crop_array_values() {
+        let mut value = serde_json::json!(["the quick brown fox jumps over the lazy dog", "fox"]);
+
+        // positions in the text indexed for this value, the second string starts at 45
+        let matches = vec![
+            MatchPosition { start: 16, length: 3 },
+            MatchPosition { start: 45, length: 3 },
+        ];
+
+        let cropped_matches = map_string_leaves(&mut value, &matches, &mut |text, positions| {
+            let leaf_matches = positions.iter().map(|m| Highlight {
+                attribute: 0,
+                char_index: m.start as u16,
+                char_length: m.length as u16,
+            });
+            let (cropped_text, cropped_matches) = crop_text(text, leaf_matches, 5, "", 1);
+            *text = cropped_text;
+            cropped_matches
+                .into_iter()
+                .map(|m| MatchPosition { start: m.char_index as usize, length: m.char_length as usize })
+                .collect()
+        });
+
+        assert_eq!(value, serde_json::json!(["fox jumps", "fox"]));
+        // "fox jumps. fox. "
+        assert_eq!(cropped_matches, vec![
+            MatchPosition { start: 0, length: 3 },
+            MatchPosition { start: 11, length: 3 },
+        ]);
+    }
+
     #[test]
     fn highlight_longest_match() {
         let data = r#"{
@@ -636,7 +1308,7 @@ mod tests {
         });
         matches.insert("title".to_string(), m);
 
-        let result = super::calculate_highlights(&document, &matches, &attributes_to_highlight);
+        let result = super::calculate_highlights(&document, &matches, &attributes_to_highlight, "<em>", "</em>");
 
         let mut result_expected = IndexMap::new();
         result_expected.insert(
@@ -646,4 +1318,18 @@ mod tests {
 
         assert_eq!(result, result_expected);
     }
+
+    #[test]
+    fn cursor_encoding() {
+        use meilisearch_core::DocumentId;
+
+        let cursor = Cursor::new(vec![Some(Number::Unsigned(42)), None], DocumentId(7));
+        let encoded = encode_cursor(&cursor);
+        assert!(encoded.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(decode_cursor(&encoded).unwrap(), cursor);
+
+        assert!(decode_cursor("not a cursor").is_err());
+        assert!(decode_cursor("abc").is_err());
+        assert!(decode_cursor("7b7d").is_err());
+    }
 }
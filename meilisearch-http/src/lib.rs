@@ -7,6 +7,8 @@ pub mod models;
 pub mod option;
 pub mod routes;
 pub mod analytics;
+pub mod search_analytics;
+pub mod search_cache;
 pub mod snapshot;
 pub mod dump;
 
@@ -83,6 +85,10 @@ pub fn index_update_callback_txn(index: Index, index_uid: &str, data: &Data, mut
 }
 
 pub fn index_update_callback(index_uid: &str, data: &Data, status: ProcessedUpdateResult) {
+    if let Some(search_cache) = &data.search_cache {
+        search_cache.invalidate(index_uid);
+    }
+
     if status.error.is_some() {
         return;
     }
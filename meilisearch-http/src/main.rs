@@ -6,7 +6,7 @@ use main_error::MainError;
 use meilisearch_http::helpers::NormalizePath;
 use meilisearch_http::{create_app, index_update_callback, Data, Opt};
 use structopt::StructOpt;
-use meilisearch_http::{snapshot, dump};
+use meilisearch_http::{snapshot, dump, search_analytics};
 
 mod analytics;
 
@@ -70,6 +70,10 @@ async fn main() -> Result<(), MainError> {
     }));
 
 
+    if let Some(analytics) = &data.search_analytics {
+        search_analytics::schedule_persistence(analytics.clone(), data.db.clone(), search_analytics::PERSISTENCE_INTERVAL);
+    }
+
     if let Some(path) = &opt.import_dump {
         dump::import_dump(&data, path, opt.dump_batch_size)?;
     }
@@ -85,6 +89,7 @@ async fn main() -> Result<(), MainError> {
         let cors = Cors::default()
                     .send_wildcard()
                     .allowed_headers(vec!["content-type", "x-meili-api-key"])
+                    .expose_headers(vec!["x-meili-next-cursor"])
                     .allow_any_origin()
                     .allow_any_method()
                     .max_age(86_400); // 24h
@@ -154,6 +159,15 @@ pub fn print_launch_resume(opt: &Opt, data: &Data) {
         }
     );
 
+    eprintln!(
+        "Search Analytics:\t{:?}",
+        if !opt.no_search_analytics {
+            "Enabled"
+        } else {
+            "Disabled"
+        }
+    );
+
     eprintln!();
 
     if data.api_keys.master.is_some() {
@@ -48,6 +48,20 @@ pub struct Opt {
     #[structopt(long, env = "MEILI_NO_ANALYTICS")]
     pub no_analytics: bool,
 
+    /// Do not record the searches made on the indexes, the search analytics are then empty.
+    #[structopt(long, env = "MEILI_NO_SEARCH_ANALYTICS")]
+    pub no_search_analytics: bool,
+
+    /// The number of searches kept for each index to compute the search analytics,
+    /// the oldest ones are dropped first. 10000 by default.
+    #[structopt(long, env = "MEILI_SEARCH_ANALYTICS_MAX_SEARCHES")]
+    pub search_analytics_max_searches: Option<usize>,
+
+    /// The maximum size, in bytes, of the search results cached in memory.
+    /// The cache is disabled when set to 0, its default.
+    #[structopt(long, env = "MEILI_SEARCH_CACHE_SIZE", default_value = "0")]
+    pub search_cache_size: usize,
+
     /// The maximum size, in bytes, of the main lmdb database directory
     #[structopt(long, env = "MEILI_MAX_MDB_SIZE", default_value = "107374182400")] // 100GB
     pub max_mdb_size: usize,
@@ -56,6 +70,11 @@ pub struct Opt {
     #[structopt(long, env = "MEILI_MAX_UDB_SIZE", default_value = "107374182400")] // 100GB
     pub max_udb_size: usize,
 
+    /// The maximum time, in milliseconds, a search can take to sort the documents.
+    /// Once elapsed the best documents found so far are returned. No limit by default.
+    #[structopt(long, env = "MEILI_SEARCH_TIMEOUT_MS")]
+    pub search_timeout_ms: Option<u64>,
+
     /// The maximum size, in bytes, of accepted JSON payloads
     #[structopt(long, env = "MEILI_HTTP_PAYLOAD_SIZE_LIMIT", default_value = "104857600")] // 100MB
     pub http_payload_size_limit: usize,
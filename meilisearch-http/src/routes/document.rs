@@ -3,17 +3,21 @@ use std::collections::{BTreeSet, HashSet};
 use actix_web::{delete, get, post, put};
 use actix_web::{web, HttpResponse};
 use indexmap::IndexMap;
-use meilisearch_core::{update, MainReader};
+use meilisearch_core::{update, Cursor, DocumentId, MainReader};
 use serde_json::Value;
 use serde::Deserialize;
 
 use crate::Data;
 use crate::error::{Error, ResponseError};
 use crate::helpers::Authentication;
+use crate::helpers::meilisearch::{decode_cursor, encode_cursor};
 use crate::routes::{IndexParam, IndexUpdateResponse};
 
 type Document = IndexMap<String, Value>;
 
+/// The header holding the cursor of the next page of documents.
+pub const NEXT_CURSOR_HEADER: &str = "X-Meili-Next-Cursor";
+
 #[derive(Deserialize)]
 struct DocumentParam {
     index_uid: String,
@@ -84,25 +88,31 @@ struct BrowseQuery {
     offset: Option<usize>,
     limit: Option<usize>,
     attributes_to_retrieve: Option<String>,
+    cursor: Option<String>,
 }
 
+/// Returns the documents ordered by their internal id, the page starts after
+/// the document the cursor points to, if any.
 pub fn get_all_documents_sync(
     data: &web::Data<Data>,
     reader: &MainReader,
     index_uid: &str,
     offset: usize,
     limit: usize,
+    cursor: Option<&Cursor>,
     attributes_to_retrieve: Option<&String>
-) -> Result<Vec<Document>, Error> {
+) -> Result<Vec<(DocumentId, Document)>, Error> {
     let index = data
         .db
         .open_index(index_uid)
         .ok_or(Error::index_not_found(index_uid))?;
 
+    let documents_ids = match cursor {
+        Some(cursor) => index.documents_fields_counts.documents_ids_after(reader, cursor.document_id)?,
+        None => index.documents_fields_counts.documents_ids(reader)?,
+    };
 
-    let documents_ids: Result<BTreeSet<_>, _> = index
-        .documents_fields_counts
-        .documents_ids(reader)?
+    let documents_ids: Result<BTreeSet<_>, _> = documents_ids
         .skip(offset)
         .take(limit)
         .collect();
@@ -115,7 +125,7 @@ pub fn get_all_documents_sync(
         if let Ok(Some(document)) =
             index.document::<Document>(reader, attributes.as_ref(), document_id)
         {
-            documents.push(document);
+            documents.push((document_id, document));
         }
     }
 
@@ -132,17 +142,34 @@ async fn get_all_documents(
     let limit = params.limit.unwrap_or(20);
     let index_uid = &path.index_uid;
     let reader = data.db.main_read_txn()?;
-    
+    let cursor = params.cursor.as_deref().map(decode_cursor).transpose()?;
+
+    // the page starts after the cursor, an offset would skip documents
+    if cursor.is_some() && offset > 0 {
+        return Err(Error::bad_parameter("cursor", "the cursor can't be used with an offset").into());
+    }
+
     let documents = get_all_documents_sync(
         &data,
         &reader,
         index_uid,
         offset,
         limit,
+        cursor.as_ref(),
         params.attributes_to_retrieve.as_ref()
     )?;
 
-    Ok(HttpResponse::Ok().json(documents))
+    // the cursor of a full page is returned in a header to keep the body a list of documents
+    let mut response = HttpResponse::Ok();
+    if documents.len() == limit {
+        if let Some((document_id, _)) = documents.last() {
+            let cursor = Cursor::new(Vec::new(), *document_id);
+            response.header(NEXT_CURSOR_HEADER, encode_cursor(&cursor));
+        }
+    }
+
+    let documents: Vec<_> = documents.into_iter().map(|(_, document)| document).collect();
+    Ok(response.json(documents))
 }
 
 fn find_primary_key(document: &IndexMap<String, Value>) -> Option<String> {
@@ -324,6 +324,12 @@ async fn delete_index(
     path: web::Path<IndexParam>,
 ) -> Result<HttpResponse, ResponseError> {
     if data.db.delete_index(&path.index_uid)? {
+        if let Some(search_analytics) = &data.search_analytics {
+            search_analytics.remove_index(&path.index_uid);
+        }
+        if let Some(search_cache) = &data.search_cache {
+            search_cache.invalidate(&path.index_uid);
+        }
         Ok(HttpResponse::NoContent().finish())
     } else {
         Err(Error::index_not_found(&path.index_uid).into())
@@ -1,24 +1,38 @@
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet, BTreeSet};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 use actix_web::{get, post, web, HttpResponse};
 use log::warn;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 
 use crate::error::{Error, FacetCountError, ResponseError};
-use crate::helpers::meilisearch::{IndexSearchExt, SearchResult};
+use crate::helpers::meilisearch::{decode_cursor, IndexSearchExt, SearchHit, SearchResult};
 use crate::helpers::Authentication;
+use crate::search_analytics::{normalize_query, SearchEvent};
 use crate::routes::IndexParam;
 use crate::Data;
 
 use meilisearch_core::facets::FacetFilter;
-use meilisearch_schema::{FieldId, Schema};
+use meilisearch_core::settings::RankingRule;
+use meilisearch_core::{MainReader, MatchingStrategy, NbHits};
+use meilisearch_schema::{FieldId, IndexedPos, Schema};
 
 pub fn services(cfg: &mut web::ServiceConfig) {
-    cfg.service(search_with_post).service(search_with_url_query);
+    cfg.service(search_with_post)
+        .service(search_with_url_query)
+        .service(federated_search)
+        .service(multi_search)
+        .service(suggestions)
+        .service(similar_documents);
 }
 
-#[derive(Serialize, Deserialize)]
+/// The longest time budget a search can ask for, in milliseconds.
+const MAX_TIMEOUT_MS: u64 = 60_000;
+
+#[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct SearchQuery {
     q: Option<String>,
@@ -32,6 +46,24 @@ pub struct SearchQuery {
     matches: Option<bool>,
     facet_filters: Option<String>,
     facets_distribution: Option<String>,
+    sort: Option<String>,
+    show_ranking_info: Option<bool>,
+    highlight_pre_tag: Option<String>,
+    highlight_post_tag: Option<String>,
+    crop_marker: Option<String>,
+    crop_snippets: Option<usize>,
+    attributes_to_search_on: Option<String>,
+    timeout_ms: Option<u64>,
+    cursor: Option<String>,
+    exhaustive_nb_hits: Option<bool>,
+    exhaustive_nb_hits_cap: Option<usize>,
+    matching_strategy: Option<String>,
+    auto_correct: Option<bool>,
+    vector: Option<String>,
+    semantic_ratio: Option<f32>,
+    ranking_rules: Option<String>,
+    distinct: Option<String>,
+    distinct_count: Option<usize>,
 }
 
 #[get("/indexes/{index_uid}/search", wrap = "Authentication::Public")]
@@ -58,6 +90,24 @@ pub struct SearchQueryPost {
     matches: Option<bool>,
     facet_filters: Option<Value>,
     facets_distribution: Option<Vec<String>>,
+    sort: Option<Vec<String>>,
+    show_ranking_info: Option<bool>,
+    highlight_pre_tag: Option<String>,
+    highlight_post_tag: Option<String>,
+    crop_marker: Option<String>,
+    crop_snippets: Option<usize>,
+    attributes_to_search_on: Option<Vec<String>>,
+    timeout_ms: Option<u64>,
+    cursor: Option<String>,
+    exhaustive_nb_hits: Option<bool>,
+    exhaustive_nb_hits_cap: Option<usize>,
+    matching_strategy: Option<String>,
+    auto_correct: Option<bool>,
+    vector: Option<Vec<f32>>,
+    semantic_ratio: Option<f32>,
+    ranking_rules: Option<Vec<String>>,
+    distinct: Option<String>,
+    distinct_count: Option<usize>,
 }
 
 impl From<SearchQueryPost> for SearchQuery {
@@ -74,6 +124,26 @@ impl From<SearchQueryPost> for SearchQuery {
             matches: other.matches,
             facet_filters: other.facet_filters.map(|f| f.to_string()),
             facets_distribution: other.facets_distribution.map(|f| format!("{:?}", f)),
+            sort: other.sort.map(|rules| rules.join(",")),
+            show_ranking_info: other.show_ranking_info,
+            highlight_pre_tag: other.highlight_pre_tag,
+            highlight_post_tag: other.highlight_post_tag,
+            crop_marker: other.crop_marker,
+            crop_snippets: other.crop_snippets,
+            attributes_to_search_on: other.attributes_to_search_on.map(|attrs| attrs.join(",")),
+            timeout_ms: other.timeout_ms,
+            cursor: other.cursor,
+            exhaustive_nb_hits: other.exhaustive_nb_hits,
+            exhaustive_nb_hits_cap: other.exhaustive_nb_hits_cap,
+            matching_strategy: other.matching_strategy,
+            auto_correct: other.auto_correct,
+            vector: other.vector.map(|vector| {
+                vector.iter().map(ToString::to_string).collect::<Vec<_>>().join(",")
+            }),
+            semantic_ratio: other.semantic_ratio,
+            ranking_rules: other.ranking_rules.map(|rules| rules.join(",")),
+            distinct: other.distinct,
+            distinct_count: other.distinct_count,
         }
     }
 }
@@ -89,21 +159,487 @@ async fn search_with_post(
     Ok(HttpResponse::Ok().json(search_result))
 }
 
+/// The largest number of hits a federated search fetches from each index,
+/// the requested page can't end after it.
+const MAX_FEDERATED_HITS: usize = 1000;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FederatedIndexQuery {
+    index_uid: String,
+    weight: Option<f64>,
+    filters: Option<String>,
+    facet_filters: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FederatedSearchQuery {
+    q: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    attributes_to_retrieve: Option<Vec<String>>,
+    attributes_to_crop: Option<Vec<String>>,
+    crop_length: Option<usize>,
+    attributes_to_highlight: Option<Vec<String>>,
+    matches: Option<bool>,
+    indexes: Vec<FederatedIndexQuery>,
+}
+
+#[derive(Serialize)]
+pub struct FederatedSearchHit {
+    #[serde(flatten)]
+    hit: SearchHit,
+    #[serde(rename = "_indexUid")]
+    index_uid: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FederatedSearchResult {
+    hits: Vec<FederatedSearchHit>,
+    offset: usize,
+    limit: usize,
+    nb_hits: usize,
+    exhaustive_nb_hits: bool,
+    partial: bool,
+    processing_time_ms: usize,
+    query: String,
+}
+
+#[post("/indexes/search", wrap = "Authentication::Public")]
+async fn federated_search(
+    data: web::Data<Data>,
+    params: web::Json<FederatedSearchQuery>,
+) -> Result<HttpResponse, ResponseError> {
+    let search_result = params.into_inner().search(&data)?;
+    Ok(HttpResponse::Ok().json(search_result))
+}
+
+/// A query of a batch, the `indexUid` field and the parameters of `SearchQueryPost`.
+pub struct MultiSearchQuery {
+    index_uid: String,
+    query: SearchQueryPost,
+}
+
+impl<'de> Deserialize<'de> for MultiSearchQuery {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<MultiSearchQuery, D::Error> {
+        // serde ignores `deny_unknown_fields` on a flattened struct,
+        // the query is therefore read from the fields left once the uid is removed
+        let mut fields = serde_json::Map::deserialize(deserializer)?;
+        let index_uid = match fields.remove("indexUid") {
+            Some(Value::String(index_uid)) => index_uid,
+            Some(_) => return Err(de::Error::custom("`indexUid` must be a string")),
+            None => return Err(de::Error::missing_field("indexUid")),
+        };
+        let query = SearchQueryPost::deserialize(Value::Object(fields)).map_err(de::Error::custom)?;
+        Ok(MultiSearchQuery { index_uid, query })
+    }
+}
+
+/// The outcome of one query of a batch, a failing query doesn't fail the other ones.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum MultiSearchResult {
+    Ok(SearchResult),
+    Err { error: ResponseError },
+}
+
+#[post("/multi-search", wrap = "Authentication::Public")]
+async fn multi_search(
+    data: web::Data<Data>,
+    params: web::Json<Vec<Value>>,
+) -> Result<HttpResponse, ResponseError> {
+    // all the queries are run on the same snapshot of the database
+    let reader = data.db.main_read_txn()?;
+
+    let results: Vec<_> = params
+        .into_inner()
+        .into_iter()
+        .map(|query| {
+            // a malformed query only fails at its own position in the batch
+            let MultiSearchQuery { index_uid, query } = match MultiSearchQuery::deserialize(query) {
+                Ok(query) => query,
+                Err(e) => {
+                    let error = Error::bad_request(format!("Invalid query: {}", e)).into();
+                    return MultiSearchResult::Err { error };
+                }
+            };
+
+            let query: SearchQuery = query.into();
+            match query.search_with_reader(&index_uid, &data, &reader) {
+                Ok(result) => {
+                    query.record(&index_uid, &data, &result, false);
+                    MultiSearchResult::Ok(result)
+                }
+                Err(error) => MultiSearchResult::Err { error },
+            }
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(results))
+}
+
+/// The largest number of suggestions a request can ask for.
+const MAX_SUGGESTIONS_LIMIT: usize = 100;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SuggestionsQuery {
+    q: String,
+    limit: Option<usize>,
+    attributes: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Suggestion {
+    word: String,
+    count: usize,
+}
+
+#[get("/indexes/{index_uid}/suggestions", wrap = "Authentication::Public")]
+async fn suggestions(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+    params: web::Query<SuggestionsQuery>,
+) -> Result<HttpResponse, ResponseError> {
+    let index = data
+        .db
+        .open_index(&path.index_uid)
+        .ok_or(Error::index_not_found(&path.index_uid))?;
+
+    let reader = data.db.main_read_txn()?;
+
+    let schema = index
+        .main
+        .schema(&reader)?
+        .ok_or(Error::internal("Impossible to retrieve the schema"))?;
+
+    let attributes = match &params.attributes {
+        Some(attributes) => {
+            let positions = prepare_searchable_attributes("attributes", attributes, &schema)?;
+            Some(positions.into_iter().collect::<HashSet<_>>())
+        }
+        None => None,
+    };
+
+    let limit = params.limit.unwrap_or(10);
+    if limit > MAX_SUGGESTIONS_LIMIT {
+        let message = format!("no more than {} suggestions can be returned", MAX_SUGGESTIONS_LIMIT);
+        return Err(Error::bad_parameter("limit", message).into());
+    }
+
+    let suggestions: Vec<_> = index
+        .suggestions(&reader, &params.q, attributes.as_ref(), limit)?
+        .into_iter()
+        .map(|(word, count)| Suggestion { word, count })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(suggestions))
+}
+
+/// The number of distinctive words of a document used to search for similar ones.
+const SIMILAR_DOCUMENTS_WORDS: usize = 10;
+
+#[derive(Deserialize)]
+pub struct SimilarDocumentsParam {
+    index_uid: String,
+    document_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SimilarDocumentsQuery {
+    offset: Option<usize>,
+    limit: Option<usize>,
+    attributes_to_retrieve: Option<String>,
+    filters: Option<String>,
+}
+
+/// Searches for the documents that share the most distinctive words of the given
+/// document, the documents sharing the most of these words are ranked first.
+#[get("/indexes/{index_uid}/documents/{document_id}/similar", wrap = "Authentication::Public")]
+async fn similar_documents(
+    data: web::Data<Data>,
+    path: web::Path<SimilarDocumentsParam>,
+    params: web::Query<SimilarDocumentsQuery>,
+) -> Result<HttpResponse, ResponseError> {
+    let index = data
+        .db
+        .open_index(&path.index_uid)
+        .ok_or(Error::index_not_found(&path.index_uid))?;
+
+    let reader = data.db.main_read_txn()?;
+
+    let document_id = index
+        .main
+        .external_to_internal_docid(&reader, &path.document_id)?
+        .ok_or(Error::document_not_found(&path.document_id))?;
+
+    let words = index.distinctive_words(&reader, document_id, SIMILAR_DOCUMENTS_WORDS)?;
+    let offset = params.offset.unwrap_or(0);
+    let limit = params.limit.unwrap_or(20);
+
+    // a document without any shared word is similar to none
+    if words.is_empty() {
+        return Ok(HttpResponse::Ok().json(SearchResult {
+            hits: Vec::new(),
+            offset,
+            limit,
+            nb_hits: 0,
+            exhaustive_nb_hits: true,
+            partial: false,
+            next_cursor: None,
+            processing_time_ms: 0,
+            query: String::new(),
+            suggested_query: None,
+            query_corrected: false,
+            facets_distribution: None,
+            exhaustive_facets_count: None,
+        }));
+    }
+
+    // the most common of the distinctive words are dropped first, the documents
+    // sharing the rarest ones are the most similar
+    let query = words.join(" ");
+
+    let mut search_builder = index.new_search(Some(query));
+    search_builder.offset(offset);
+    search_builder.limit(limit);
+    search_builder.exclude_document(document_id);
+    search_builder.matching_strategy(MatchingStrategy::Frequency);
+    search_builder.exact_words(true);
+
+    if let Some(attributes) = &params.attributes_to_retrieve {
+        let schema = index
+            .main
+            .schema(&reader)?
+            .ok_or(Error::internal("Impossible to retrieve the schema"))?;
+        let available_attributes = schema.displayed_names();
+
+        let attributes: HashSet<&str> = attributes.split(',').collect();
+        if !attributes.contains("*") {
+            search_builder.attributes_to_retrieve(HashSet::new());
+            for attribute in attributes {
+                if available_attributes.contains(attribute) {
+                    search_builder.add_retrievable_field(attribute.to_string());
+                } else {
+                    warn!("The attributes {:?} present in attributesToRetrieve parameter doesn't exist", attribute);
+                }
+            }
+        }
+    }
+
+    if let Some(filters) = &params.filters {
+        search_builder.filters(filters.to_string());
+    }
+
+    if let Some(timeout) = data.search_timeout {
+        search_builder.timeout(timeout);
+    }
+
+    let search_result = search_builder.search(&reader)?;
+
+    // the words of the document are not a query typed by a user
+    if let Some(search_analytics) = &data.search_analytics {
+        let filters = params.filters.iter().cloned().collect();
+        let event = SearchEvent::new(
+            None,
+            search_result.nb_hits,
+            filters,
+            search_result.processing_time_ms,
+            false,
+        );
+        search_analytics.record(&path.index_uid, event);
+    }
+
+    Ok(HttpResponse::Ok().json(search_result))
+}
+
+impl FederatedSearchQuery {
+    /// Runs the query against every requested index and merges the hits in a single list.
+    ///
+    /// The relevancy computed by one index can't be compared to the one of another index,
+    /// each hit is therefore scored by its rank in its own index, scaled by the weight of
+    /// this index. Hits with the same score keep the order in which the indexes were given.
+    fn search(self, data: &web::Data<Data>) -> Result<FederatedSearchResult, ResponseError> {
+        if self.indexes.is_empty() {
+            return Err(Error::bad_request("At least one index must be given to perform a federated search").into());
+        }
+
+        let start = Instant::now();
+        let offset = self.offset.unwrap_or(0);
+        let limit = self.limit.unwrap_or(20);
+
+        // every index must return enough hits to fill the requested page on its own
+        let fetched = match offset.checked_add(limit) {
+            Some(fetched) if fetched <= MAX_FEDERATED_HITS => fetched,
+            _ => return Err(Error::bad_request(format!(
+                "A federated search can't return the hits after the first {}",
+                MAX_FEDERATED_HITS,
+            )).into()),
+        };
+
+        let reader = data.db.main_read_txn()?;
+
+        let mut nb_hits = 0;
+        let mut exhaustive_nb_hits = true;
+        let mut partial = false;
+        let mut scored_hits = Vec::new();
+
+        for index_query in &self.indexes {
+            let weight = index_query.weight.unwrap_or(1.0);
+            if !weight.is_finite() || weight <= 0.0 {
+                return Err(Error::bad_request(format!(
+                    "The weight of the index {} must be a positive number",
+                    index_query.index_uid,
+                )).into());
+            }
+
+            let query = SearchQuery {
+                q: self.q.clone(),
+                offset: Some(0),
+                limit: Some(fetched),
+                attributes_to_retrieve: self.attributes_to_retrieve.as_ref().map(|attrs| attrs.join(",")),
+                attributes_to_crop: self.attributes_to_crop.as_ref().map(|attrs| attrs.join(",")),
+                crop_length: self.crop_length,
+                attributes_to_highlight: self.attributes_to_highlight.as_ref().map(|attrs| attrs.join(",")),
+                filters: index_query.filters.clone(),
+                matches: self.matches,
+                facet_filters: index_query.facet_filters.as_ref().map(|f| f.to_string()),
+                facets_distribution: None,
+                sort: None,
+                show_ranking_info: None,
+                highlight_pre_tag: None,
+                highlight_post_tag: None,
+                crop_marker: None,
+                crop_snippets: None,
+                attributes_to_search_on: None,
+                timeout_ms: None,
+                cursor: None,
+                exhaustive_nb_hits: None,
+                exhaustive_nb_hits_cap: None,
+                matching_strategy: None,
+                auto_correct: None,
+                vector: None,
+                semantic_ratio: None,
+                ranking_rules: None,
+                distinct: None,
+                distinct_count: None,
+            };
+
+            let result = query.search_with_reader(&index_query.index_uid, data, &reader)?;
+            query.record(&index_query.index_uid, data, &result, false);
+            nb_hits += result.nb_hits;
+            exhaustive_nb_hits &= result.exhaustive_nb_hits;
+            partial |= result.partial;
+
+            for (rank, hit) in result.hits.into_iter().enumerate() {
+                let score = weight / (rank + 1) as f64;
+                let hit = FederatedSearchHit { hit, index_uid: index_query.index_uid.clone() };
+                scored_hits.push((score, hit));
+            }
+        }
+
+        // the sort is stable, hits with the same score stay in the indexes order
+        scored_hits.sort_by(|(a, _), (b, _)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+
+        let hits = scored_hits
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(_, hit)| hit)
+            .collect();
+
+        Ok(FederatedSearchResult {
+            hits,
+            offset,
+            limit,
+            nb_hits,
+            exhaustive_nb_hits,
+            partial,
+            processing_time_ms: start.elapsed().as_millis() as usize,
+            query: self.q.unwrap_or_default(),
+        })
+    }
+}
+
 impl SearchQuery {
     fn search(
         &self,
         index_uid: &str,
         data: web::Data<Data>,
+    ) -> Result<SearchResult, ResponseError> {
+        let (search_result, cached) = match &data.search_cache {
+            Some(search_cache) => {
+                let start = Instant::now();
+                let key = self.cache_key();
+                match search_cache.get(index_uid, &key) {
+                    Some(mut search_result) => {
+                        search_result.query = self.q.clone().unwrap_or_default();
+                        search_result.processing_time_ms = start.elapsed().as_millis() as usize;
+                        (search_result, true)
+                    }
+                    None => {
+                        let generation = search_cache.generation(index_uid);
+                        let reader = data.db.main_read_txn()?;
+                        let search_result = self.search_with_reader(index_uid, &data, &reader)?;
+                        // a result cut by the timeout could be completed by the next search
+                        if !search_result.partial {
+                            search_cache.insert(index_uid, generation, key, &search_result);
+                        }
+                        (search_result, false)
+                    }
+                }
+            }
+            None => {
+                let reader = data.db.main_read_txn()?;
+                (self.search_with_reader(index_uid, &data, &reader)?, false)
+            }
+        };
+
+        self.record(index_uid, &data, &search_result, cached);
+
+        Ok(search_result)
+    }
+
+    /// Records the search in the analytics of the index, if they are enabled.
+    fn record(&self, index_uid: &str, data: &Data, search_result: &SearchResult, cached: bool) {
+        if let Some(search_analytics) = &data.search_analytics {
+            let filters = self.filters.iter().chain(&self.facet_filters).cloned().collect();
+            let event = SearchEvent::new(
+                self.q.as_deref(),
+                search_result.nb_hits,
+                filters,
+                search_result.processing_time_ms,
+                cached,
+            );
+            search_analytics.record(index_uid, event);
+        }
+    }
+
+    /// Identifies the results of this search in the cache, the queries only differing by
+    /// their case or spacing share the same results.
+    fn cache_key(&self) -> String {
+        let mut query = self.clone();
+        query.q = query.q.map(|q| normalize_query(&q));
+        serde_json::to_string(&query).unwrap_or_default()
+    }
+
+    fn search_with_reader(
+        &self,
+        index_uid: &str,
+        data: &web::Data<Data>,
+        reader: &MainReader,
     ) -> Result<SearchResult, ResponseError> {
         let index = data
             .db
             .open_index(index_uid)
             .ok_or(Error::index_not_found(index_uid))?;
 
-        let reader = data.db.main_read_txn()?;
         let schema = index
             .main
-            .schema(&reader)?
+            .schema(reader)?
             .ok_or(Error::internal("Impossible to retrieve the schema"))?;
 
         let query = self
@@ -149,7 +685,7 @@ impl SearchQuery {
         if let Some(ref facet_filters) = self.facet_filters {
             let attrs = index
                 .main
-                .attributes_for_faceting(&reader)?
+                .attributes_for_faceting(reader)?
                 .unwrap_or_default();
             search_builder.add_facet_filters(FacetFilter::from_str(
                 facet_filters,
@@ -159,7 +695,7 @@ impl SearchQuery {
         }
 
         if let Some(facets) = &self.facets_distribution {
-            match index.main.attributes_for_faceting(&reader)? {
+            match index.main.attributes_for_faceting(reader)? {
                 Some(ref attrs) => {
                     let field_ids = prepare_facet_list(&facets, &schema, attrs)?;
                     search_builder.add_facets(field_ids);
@@ -224,8 +760,246 @@ impl SearchQuery {
                 search_builder.get_matches();
             }
         }
-        search_builder.search(&reader)
+
+        if let Some(sort) = &self.sort {
+            search_builder.sort(prepare_sort(sort, &schema)?);
+        }
+
+        if let Some(ranking_rules) = &self.ranking_rules {
+            search_builder.ranking_rules(prepare_ranking_rules(ranking_rules, &schema)?);
+        }
+
+        if let Some(distinct) = &self.distinct {
+            match schema.id(distinct) {
+                Some(field_id) => search_builder.distinct(field_id),
+                None => return Err(Error::bad_parameter(
+                    "distinct",
+                    format!("{} is not an attribute of the documents", distinct),
+                ).into()),
+            };
+        }
+
+        // the documents kept for each value of the distinct attribute
+        if let Some(distinct_count) = self.distinct_count {
+            if distinct_count == 0 {
+                return Err(Error::bad_parameter("distinctCount", "the distinct count must be at least 1").into());
+            }
+            search_builder.distinct_count(distinct_count);
+        }
+
+        if let Some(true) = self.show_ranking_info {
+            search_builder.get_ranking_infos();
+        }
+
+        if let Some(pre_tag) = &self.highlight_pre_tag {
+            search_builder.highlight_pre_tag(pre_tag.to_string());
+        }
+
+        if let Some(post_tag) = &self.highlight_post_tag {
+            search_builder.highlight_post_tag(post_tag.to_string());
+        }
+
+        if let Some(crop_marker) = &self.crop_marker {
+            search_builder.crop_marker(crop_marker.to_string());
+        }
+
+        if let Some(crop_snippets) = self.crop_snippets {
+            search_builder.crop_snippets(crop_snippets);
+        }
+
+        if let Some(attributes) = &self.attributes_to_search_on {
+            search_builder.attributes_to_search_on(prepare_searchable_attributes("attributesToSearchOn", attributes, &schema)?);
+        }
+
+        if let Some(timeout_ms) = self.timeout_ms {
+            if timeout_ms > MAX_TIMEOUT_MS {
+                let message = format!("the time budget can't exceed {}ms", MAX_TIMEOUT_MS);
+                return Err(Error::bad_parameter("timeoutMs", message).into());
+            }
+        }
+
+        // the timeout of the request takes precedence over the one of the server
+        if let Some(timeout) = self.timeout_ms.map(Duration::from_millis).or(data.search_timeout) {
+            search_builder.timeout(timeout);
+        }
+
+        if let Some(cursor) = &self.cursor {
+            // the page starts after the cursor, an offset would skip documents
+            if self.offset.unwrap_or(0) > 0 {
+                return Err(Error::bad_parameter("cursor", "the cursor can't be used with an offset").into());
+            }
+            search_builder.cursor(decode_cursor(cursor)?);
+        }
+
+        // the cap keeps the cost of an exact count bounded, it takes precedence
+        if let Some(cap) = self.exhaustive_nb_hits_cap {
+            search_builder.nb_hits(NbHits::Capped(cap));
+        } else if let Some(true) = self.exhaustive_nb_hits {
+            search_builder.nb_hits(NbHits::Exhaustive);
+        }
+
+        if let Some(strategy) = &self.matching_strategy {
+            search_builder.matching_strategy(prepare_matching_strategy(strategy)?);
+        }
+
+        if let Some(auto_correct) = self.auto_correct {
+            search_builder.auto_correct(auto_correct);
+        }
+
+        if let Some(vector) = &self.vector {
+            search_builder.vector(prepare_vector(vector)?);
+        }
+
+        if let Some(ratio) = self.semantic_ratio {
+            if !(0.0..=1.0).contains(&ratio) {
+                return Err(Error::bad_parameter(
+                    "semanticRatio",
+                    format!("{} is not a valid semantic ratio, expected a number between 0 and 1", ratio),
+                ).into());
+            }
+            search_builder.semantic_ratio(ratio);
+        }
+
+        search_builder.search(reader)
+    }
+}
+
+/// Parses the `matchingStrategy` parameter, either `all`, `last` or `frequency`.
+fn prepare_matching_strategy(strategy: &str) -> Result<MatchingStrategy, Error> {
+    match strategy {
+        "all" => Ok(MatchingStrategy::All),
+        "last" => Ok(MatchingStrategy::Last),
+        "frequency" => Ok(MatchingStrategy::Frequency),
+        _ => Err(Error::bad_parameter(
+            "matchingStrategy",
+            format!("{} is not a valid matching strategy, expected all, last or frequency", strategy),
+        )),
+    }
+}
+
+/// Parses the comma separated numbers of the `vector` parameter.
+fn prepare_vector(vector: &str) -> Result<Vec<f32>, Error> {
+    vector
+        .split(',')
+        .map(|number| number.trim().parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| Error::bad_parameter(
+            "vector",
+            format!("{} is not a valid vector, expected comma separated numbers", vector),
+        ))
+}
+
+/// Parses the comma separated list of attributes of the given parameter into their
+/// indexed positions, ordered like the searchable attributes of the index.
+///
+/// An error is returned if an attribute is not searchable, `*` selects all of them.
+fn prepare_searchable_attributes(param: &str, attributes: &str, schema: &Schema) -> Result<Vec<IndexedPos>, Error> {
+    let mut positions = Vec::new();
+    for attribute in attributes.split(',').filter(|s| !s.is_empty()) {
+        if attribute == "*" {
+            let all = schema.searchable_names().into_iter().filter_map(|name| schema.id(name));
+            positions.extend(all.filter_map(|id| schema.is_searchable(id)));
+            continue;
+        }
+
+        match schema.id(attribute).and_then(|id| schema.is_searchable(id)) {
+            Some(position) => positions.push(position),
+            None => return Err(Error::bad_parameter(
+                param,
+                format!("{} is not a searchable attribute", attribute),
+            )),
+        }
+    }
+
+    positions.sort_unstable();
+    positions.dedup();
+    Ok(positions)
+}
+
+/// Parses the comma separated list of `asc(attribute)`, `desc(attribute)` and
+/// `_geoPoint(lat, lng)` rules of the `sort` parameter.
+///
+/// An error is returned if a rule is malformed, or if its attribute can't be sorted on,
+/// only the attributes registered for ranking can be.
+fn prepare_sort(sort: &str, schema: &Schema) -> Result<Vec<RankingRule>, Error> {
+    let mut rules = Vec::new();
+    for rule in split_sort_rules(sort) {
+        let rule = match RankingRule::from_str(rule) {
+            Ok(rule) if rule.is_custom() => rule,
+            _ => return Err(Error::bad_parameter(
+                "sort",
+                format!(
+                    "{} is not a valid sort rule, expected asc(attribute), desc(attribute) or _geoPoint(lat, lng)",
+                    rule,
+                ),
+            )),
+        };
+
+        check_rule_field("sort", &rule, schema)?;
+        rules.push(rule);
+    }
+    Ok(rules)
+}
+
+/// Parses the comma separated ranking rules of the `rankingRules` parameter, written like
+/// the ones of the settings, they replace the ranking rules of the index for this search.
+///
+/// An error is returned if a rule is malformed, or if its attribute can't be sorted on.
+fn prepare_ranking_rules(ranking_rules: &str, schema: &Schema) -> Result<Vec<RankingRule>, Error> {
+    let mut rules = Vec::new();
+    for rule in split_sort_rules(ranking_rules) {
+        let rule = RankingRule::from_str(rule).map_err(|_| Error::bad_parameter(
+            "rankingRules",
+            format!("{} is not a valid ranking rule", rule),
+        ))?;
+
+        check_rule_field("rankingRules", &rule, schema)?;
+        rules.push(rule);
+    }
+
+    if rules.is_empty() {
+        return Err(Error::bad_parameter("rankingRules", "at least one ranking rule must be given"));
+    }
+    RankingRule::check_all(&rules).map_err(|e| Error::bad_parameter("rankingRules", e))?;
+
+    Ok(rules)
+}
+
+/// Only the attributes registered for ranking can be sorted on during a search.
+fn check_rule_field(param: &str, rule: &RankingRule, schema: &Schema) -> Result<(), Error> {
+    let field = match rule.field() {
+        Some(field) => field,
+        None => return Ok(()),
+    };
+
+    match schema.id(field) {
+        Some(id) if schema.is_ranked(id) => Ok(()),
+        _ => Err(Error::bad_parameter(
+            param,
+            format!("{} can't be applied, the attribute is not sortable", rule),
+        )),
+    }
+}
+
+/// Splits the rules of the `sort` and `rankingRules` parameters on the commas that are not
+/// between parentheses, the coordinates of a geo point are separated by one.
+fn split_sort_rules(sort: &str) -> Vec<&str> {
+    let mut rules = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in sort.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                rules.push(&sort[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
     }
+    rules.push(&sort[start..]);
+    rules.into_iter().map(str::trim).filter(|s| !s.is_empty()).collect()
 }
 
 /// Parses the incoming string into an array of attributes for which to return a count. It returns
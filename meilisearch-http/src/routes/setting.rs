@@ -3,7 +3,7 @@ use std::collections::{BTreeMap, BTreeSet};
 use actix_web::{delete, get, post};
 use actix_web::{web, HttpResponse};
 use meilisearch_core::{MainReader, UpdateWriter};
-use meilisearch_core::settings::{Settings, SettingsUpdate, UpdateState, DEFAULT_RANKING_RULES};
+use meilisearch_core::settings::{QueryRule, RankingRule, Settings, SettingsUpdate, TypoTolerance, UpdateState, DEFAULT_RANKING_RULES};
 use meilisearch_schema::Schema;
 
 use crate::Data;
@@ -29,7 +29,16 @@ pub fn services(cfg: &mut web::ServiceConfig) {
         .service(delete_displayed)
         .service(get_attributes_for_faceting)
         .service(delete_attributes_for_faceting)
-        .service(update_attributes_for_faceting);
+        .service(update_attributes_for_faceting)
+        .service(get_sortable)
+        .service(update_sortable)
+        .service(delete_sortable)
+        .service(get_typo_tolerance)
+        .service(update_typo_tolerance)
+        .service(delete_typo_tolerance)
+        .service(get_query_rules)
+        .service(update_query_rules)
+        .service(delete_query_rules);
 }
 
 pub fn update_all_settings_txn(
@@ -55,7 +64,17 @@ async fn update_all(
 ) -> Result<HttpResponse, ResponseError> {
     let update_id = data.get_or_create_index(&path.index_uid, |index| {
         Ok(data.db.update_write::<_, _, ResponseError>(|writer| {
-            let settings = body.into_inner().to_update().map_err(Error::bad_request)?;
+            let settings = body.into_inner();
+            if let Some(Some(typo_tolerance)) = &settings.typo_tolerance {
+                typo_tolerance.check().map_err(Error::bad_request)?;
+            }
+            if let Some(Some(query_rules)) = &settings.query_rules {
+                QueryRule::check_all(query_rules).map_err(Error::bad_request)?;
+            }
+            let settings = settings.to_update().map_err(Error::bad_request)?;
+            if let UpdateState::Update(ranking_rules) = &settings.ranking_rules {
+                RankingRule::check_all(ranking_rules).map_err(Error::bad_request)?;
+            }
             let update_id = index.settings_update(writer, settings)?;
             Ok(update_id)
         })?)
@@ -105,6 +124,10 @@ pub fn get_all_sync(data: &web::Data<Data>, reader: &MainReader, index_uid: &str
         _ => vec![],
     };
 
+    let sortable_attributes = index.main.sortable_attributes(reader)?.unwrap_or_default();
+    let typo_tolerance = index.main.typo_tolerance(reader)?.unwrap_or_default();
+    let query_rules = index.main.query_rules(reader)?.unwrap_or_default();
+
     let searchable_attributes = schema.as_ref().map(get_indexed_attributes);
     let displayed_attributes = schema.as_ref().map(get_displayed_attributes);
 
@@ -116,6 +139,9 @@ pub fn get_all_sync(data: &web::Data<Data>, reader: &MainReader, index_uid: &str
         stop_words: Some(Some(stop_words)),
         synonyms: Some(Some(synonyms)),
         attributes_for_faceting: Some(Some(attributes_for_faceting)),
+        sortable_attributes: Some(Some(sortable_attributes)),
+        typo_tolerance: Some(Some(typo_tolerance)),
+        query_rules: Some(Some(query_rules)),
     })
 }
 
@@ -149,6 +175,9 @@ async fn delete_all(
         stop_words: UpdateState::Clear,
         synonyms: UpdateState::Clear,
         attributes_for_faceting: UpdateState::Clear,
+        sortable_attributes: UpdateState::Clear,
+        typo_tolerance: UpdateState::Clear,
+        query_rules: UpdateState::Clear,
     };
 
     let update_id = data
@@ -199,6 +228,9 @@ async fn update_rules(
         };
 
         let settings = settings.to_update().map_err(Error::bad_request)?;
+        if let UpdateState::Update(ranking_rules) = &settings.ranking_rules {
+            RankingRule::check_all(ranking_rules).map_err(Error::bad_request)?;
+        }
         Ok(data
             .db
             .update_write(|w| index.settings_update(w, settings))?)
@@ -522,6 +554,220 @@ async fn delete_attributes_for_faceting(
     Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
 }
 
+#[get(
+    "/indexes/{index_uid}/settings/sortable-attributes",
+    wrap = "Authentication::Private"
+)]
+async fn get_sortable(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+) -> Result<HttpResponse, ResponseError> {
+    let index = data
+        .db
+        .open_index(&path.index_uid)
+        .ok_or(Error::index_not_found(&path.index_uid))?;
+    let reader = data.db.main_read_txn()?;
+
+    let sortable_attributes = index.main.sortable_attributes(&reader)?.unwrap_or_default();
+
+    Ok(HttpResponse::Ok().json(sortable_attributes))
+}
+
+#[post(
+    "/indexes/{index_uid}/settings/sortable-attributes",
+    wrap = "Authentication::Private"
+)]
+async fn update_sortable(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+    body: web::Json<Option<Vec<String>>>,
+) -> Result<HttpResponse, ResponseError> {
+    let update_id = data.get_or_create_index(&path.index_uid, |index| {
+        let settings = Settings {
+            sortable_attributes: Some(body.into_inner()),
+            ..Settings::default()
+        };
+
+        let settings = settings.to_update().map_err(Error::bad_request)?;
+        Ok(data
+            .db
+            .update_write(|w| index.settings_update(w, settings))?)
+    })?;
+
+    Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
+}
+
+#[delete(
+    "/indexes/{index_uid}/settings/sortable-attributes",
+    wrap = "Authentication::Private"
+)]
+async fn delete_sortable(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+) -> Result<HttpResponse, ResponseError> {
+    let index = data
+        .db
+        .open_index(&path.index_uid)
+        .ok_or(Error::index_not_found(&path.index_uid))?;
+
+    let settings = SettingsUpdate {
+        sortable_attributes: UpdateState::Clear,
+        ..SettingsUpdate::default()
+    };
+
+    let update_id = data
+        .db
+        .update_write(|w| index.settings_update(w, settings))?;
+
+    Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
+}
+
+#[get(
+    "/indexes/{index_uid}/settings/typo-tolerance",
+    wrap = "Authentication::Private"
+)]
+async fn get_typo_tolerance(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+) -> Result<HttpResponse, ResponseError> {
+    let index = data
+        .db
+        .open_index(&path.index_uid)
+        .ok_or(Error::index_not_found(&path.index_uid))?;
+    let reader = data.db.main_read_txn()?;
+
+    let typo_tolerance = index.main.typo_tolerance(&reader)?.unwrap_or_default();
+
+    Ok(HttpResponse::Ok().json(typo_tolerance))
+}
+
+#[post(
+    "/indexes/{index_uid}/settings/typo-tolerance",
+    wrap = "Authentication::Private"
+)]
+async fn update_typo_tolerance(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+    body: web::Json<Option<TypoTolerance>>,
+) -> Result<HttpResponse, ResponseError> {
+    let typo_tolerance = body.into_inner();
+    if let Some(typo_tolerance) = &typo_tolerance {
+        typo_tolerance.check().map_err(Error::bad_request)?;
+    }
+
+    let update_id = data.get_or_create_index(&path.index_uid, |index| {
+        let settings = Settings {
+            typo_tolerance: Some(typo_tolerance),
+            ..Settings::default()
+        };
+
+        let settings = settings.to_update().map_err(Error::bad_request)?;
+        Ok(data
+            .db
+            .update_write(|w| index.settings_update(w, settings))?)
+    })?;
+
+    Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
+}
+
+#[delete(
+    "/indexes/{index_uid}/settings/typo-tolerance",
+    wrap = "Authentication::Private"
+)]
+async fn delete_typo_tolerance(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+) -> Result<HttpResponse, ResponseError> {
+    let index = data
+        .db
+        .open_index(&path.index_uid)
+        .ok_or(Error::index_not_found(&path.index_uid))?;
+
+    let settings = SettingsUpdate {
+        typo_tolerance: UpdateState::Clear,
+        ..SettingsUpdate::default()
+    };
+
+    let update_id = data
+        .db
+        .update_write(|w| index.settings_update(w, settings))?;
+
+    Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
+}
+
+#[get(
+    "/indexes/{index_uid}/settings/query-rules",
+    wrap = "Authentication::Private"
+)]
+async fn get_query_rules(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+) -> Result<HttpResponse, ResponseError> {
+    let index = data
+        .db
+        .open_index(&path.index_uid)
+        .ok_or(Error::index_not_found(&path.index_uid))?;
+    let reader = data.db.main_read_txn()?;
+
+    let query_rules = index.main.query_rules(&reader)?.unwrap_or_default();
+
+    Ok(HttpResponse::Ok().json(query_rules))
+}
+
+#[post(
+    "/indexes/{index_uid}/settings/query-rules",
+    wrap = "Authentication::Private"
+)]
+async fn update_query_rules(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+    body: web::Json<Option<Vec<QueryRule>>>,
+) -> Result<HttpResponse, ResponseError> {
+    let query_rules = body.into_inner();
+    if let Some(query_rules) = &query_rules {
+        QueryRule::check_all(query_rules).map_err(Error::bad_request)?;
+    }
+
+    let update_id = data.get_or_create_index(&path.index_uid, |index| {
+        let settings = Settings {
+            query_rules: Some(query_rules),
+            ..Settings::default()
+        };
+
+        let settings = settings.to_update().map_err(Error::bad_request)?;
+        Ok(data
+            .db
+            .update_write(|w| index.settings_update(w, settings))?)
+    })?;
+
+    Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
+}
+
+#[delete(
+    "/indexes/{index_uid}/settings/query-rules",
+    wrap = "Authentication::Private"
+)]
+async fn delete_query_rules(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+) -> Result<HttpResponse, ResponseError> {
+    let index = data
+        .db
+        .open_index(&path.index_uid)
+        .ok_or(Error::index_not_found(&path.index_uid))?;
+
+    let settings = SettingsUpdate {
+        query_rules: UpdateState::Clear,
+        ..SettingsUpdate::default()
+    };
+
+    let update_id = data
+        .db
+        .update_write(|w| index.settings_update(w, settings))?;
+
+    Ok(HttpResponse::Accepted().json(IndexUpdateResponse::with_id(update_id)))
+}
+
 fn get_indexed_attributes(schema: &Schema) -> Vec<String> {
     if schema.is_searchable_all() {
         vec!["*".to_string()]
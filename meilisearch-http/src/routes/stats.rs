@@ -1,20 +1,23 @@
 use std::collections::{HashMap, BTreeMap};
+use std::time::Duration;
 
 use actix_web::web;
 use actix_web::HttpResponse;
 use actix_web::get;
 use chrono::{DateTime, Utc};
 use log::error;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
 use crate::error::{Error, ResponseError};
 use crate::helpers::Authentication;
+use crate::search_cache::SearchCacheStats;
 use crate::routes::IndexParam;
 use crate::Data;
 
 pub fn services(cfg: &mut web::ServiceConfig) {
     cfg.service(index_stats)
+        .service(index_search_analytics)
         .service(get_stats)
         .service(get_version);
 }
@@ -58,12 +61,50 @@ async fn index_stats(
     }))
 }
 
+/// The number of queries and filters listed by default in the search analytics.
+const DEFAULT_SEARCH_ANALYTICS_LIMIT: usize = 20;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct SearchAnalyticsQuery {
+    window_sec: Option<u64>,
+    limit: Option<usize>,
+}
+
+#[get("/indexes/{index_uid}/stats/search", wrap = "Authentication::Private")]
+async fn index_search_analytics(
+    data: web::Data<Data>,
+    path: web::Path<IndexParam>,
+    params: web::Query<SearchAnalyticsQuery>,
+) -> Result<HttpResponse, ResponseError> {
+    if data.db.open_index(&path.index_uid).is_none() {
+        return Err(Error::index_not_found(&path.index_uid).into());
+    }
+
+    let search_analytics = data
+        .search_analytics
+        .as_ref()
+        .ok_or(Error::bad_request("The search analytics are disabled on this server"))?;
+
+    // without window all the searches kept in memory are analyzed
+    let since = params
+        .window_sec
+        .and_then(|secs| chrono::Duration::from_std(Duration::from_secs(secs)).ok())
+        .and_then(|window| Utc::now().checked_sub_signed(window))
+        .unwrap_or(chrono::MIN_DATETIME);
+    let limit = params.limit.unwrap_or(DEFAULT_SEARCH_ANALYTICS_LIMIT);
+
+    Ok(HttpResponse::Ok().json(search_analytics.report(&path.index_uid, since, limit)))
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct StatsResult {
     database_size: u64,
     last_update: Option<DateTime<Utc>>,
     indexes: HashMap<String, IndexStatsResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    search_cache: Option<SearchCacheStats>,
 }
 
 #[get("/stats", wrap = "Authentication::Private")]
@@ -113,6 +154,7 @@ async fn get_stats(data: web::Data<Data>) -> Result<HttpResponse, ResponseError>
         database_size,
         last_update,
         indexes: index_list,
+        search_cache: data.search_cache.as_ref().map(|cache| cache.stats()),
     }))
 }
 
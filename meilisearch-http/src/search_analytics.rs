@@ -0,0 +1,342 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use log::error;
+use meilisearch_core::Database;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// The number of searches kept for each index when no limit is given.
+pub const DEFAULT_MAX_SEARCHES: usize = 10_000;
+
+/// The time between two writes of the recorded searches to the indexes.
+pub const PERSISTENCE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A search made on an index, recorded to compute the search analytics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchEvent {
+    pub date: DateTime<Utc>,
+    /// The normalized query, empty for a placeholder search.
+    pub query: String,
+    pub nb_hits: usize,
+    /// The filter expression and the facet filters of the search.
+    pub filters: Vec<String>,
+    pub processing_time_ms: usize,
+    /// The results were served by the search cache, the processing time is not the one
+    /// of a search and is left out of the latencies.
+    #[serde(default)]
+    pub cached: bool,
+}
+
+impl SearchEvent {
+    pub fn new(
+        query: Option<&str>,
+        nb_hits: usize,
+        filters: Vec<String>,
+        processing_time_ms: usize,
+        cached: bool,
+    ) -> SearchEvent {
+        SearchEvent {
+            date: Utc::now(),
+            query: query.map(normalize_query).unwrap_or_default(),
+            nb_hits,
+            filters,
+            processing_time_ms,
+            cached,
+        }
+    }
+}
+
+/// Lowercases the query and separates its words by a single space, so that the
+/// same query typed differently is counted once.
+pub fn normalize_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The last searches made on each index. They never leave the server, the oldest searches
+/// of an index are dropped once the limit is reached.
+///
+/// The searches are recorded in memory and regularly written to the main store of their
+/// index, the ones recorded since the last write are lost if the server stops.
+pub struct SearchAnalytics {
+    max_searches: usize,
+    events: Mutex<HashMap<String, VecDeque<SearchEvent>>>,
+    /// The indexes with searches recorded since the last write.
+    dirty: Mutex<HashSet<String>>,
+}
+
+impl SearchAnalytics {
+    pub fn new(max_searches: usize) -> SearchAnalytics {
+        SearchAnalytics {
+            max_searches,
+            events: Mutex::new(HashMap::new()),
+            dirty: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Reads the searches previously written to the indexes of the database.
+    pub fn load(&self, db: &Database) -> Result<(), Error> {
+        let reader = db.main_read_txn()?;
+        let mut events = self.events.lock().unwrap();
+
+        for index_uid in db.indexes_uids() {
+            let index = match db.open_index(&index_uid) {
+                Some(index) => index,
+                None => continue,
+            };
+
+            if let Some(bytes) = index.main.search_events(&reader)? {
+                // the searches of an index are only statistics, they must not prevent the start
+                let mut index_events: VecDeque<SearchEvent> = match serde_json::from_slice(bytes) {
+                    Ok(index_events) => index_events,
+                    Err(e) => {
+                        error!("Unsuccessful search analytics read of the {} index: {}", index_uid, e);
+                        continue;
+                    }
+                };
+                while index_events.len() > self.max_searches {
+                    index_events.pop_front();
+                }
+                events.insert(index_uid, index_events);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the searches recorded since the last write to the main store of their index.
+    pub fn persist(&self, db: &Database) -> Result<(), Error> {
+        let dirty: Vec<_> = self.dirty.lock().unwrap().drain().collect();
+        if dirty.is_empty() {
+            return Ok(());
+        }
+
+        let mut serialized = Vec::with_capacity(dirty.len());
+        {
+            let events = self.events.lock().unwrap();
+            for index_uid in dirty {
+                if let Some(index_events) = events.get(&index_uid) {
+                    serialized.push((index_uid, serde_json::to_vec(index_events)?));
+                }
+            }
+        }
+
+        db.main_write::<_, _, Error>(|writer| {
+            for (index_uid, bytes) in &serialized {
+                // the index may have been deleted since the search
+                if let Some(index) = db.open_index(index_uid) {
+                    index.main.put_search_events(writer, bytes)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    pub fn record(&self, index_uid: &str, event: SearchEvent) {
+        if self.max_searches == 0 {
+            return;
+        }
+
+        let mut events = self.events.lock().unwrap();
+        let events = events.entry(index_uid.to_string()).or_default();
+        if events.len() >= self.max_searches {
+            events.pop_front();
+        }
+        events.push_back(event);
+
+        self.dirty.lock().unwrap().insert(index_uid.to_string());
+    }
+
+    pub fn remove_index(&self, index_uid: &str) {
+        self.events.lock().unwrap().remove(index_uid);
+        self.dirty.lock().unwrap().remove(index_uid);
+    }
+
+    /// Computes the analytics of the searches made on the index since the given date,
+    /// the top lists are truncated to the limit.
+    pub fn report(&self, index_uid: &str, since: DateTime<Utc>, limit: usize) -> SearchAnalyticsReport {
+        let events = self.events.lock().unwrap();
+        let events: Vec<_> = match events.get(index_uid) {
+            Some(events) => events.iter().filter(|e| e.date >= since).collect(),
+            None => Vec::new(),
+        };
+
+        let mut queries = HashMap::new();
+        let mut zero_result_queries = HashMap::new();
+        let mut filters = HashMap::new();
+        for event in &events {
+            if !event.query.is_empty() {
+                *queries.entry(event.query.as_str()).or_insert(0) += 1;
+                if event.nb_hits == 0 {
+                    *zero_result_queries.entry(event.query.as_str()).or_insert(0) += 1;
+                }
+            }
+            for filter in &event.filters {
+                *filters.entry(filter.as_str()).or_insert(0) += 1;
+            }
+        }
+
+        let mut latencies: Vec<_> = events
+            .iter()
+            .filter(|e| !e.cached)
+            .map(|e| e.processing_time_ms)
+            .collect();
+        latencies.sort_unstable();
+
+        SearchAnalyticsReport {
+            number_of_searches: events.len(),
+            top_queries: top_counts(queries, limit),
+            top_zero_result_queries: top_counts(zero_result_queries, limit),
+            top_filters: top_counts(filters, limit),
+            processing_time_ms: LatencyPercentiles::from_sorted(&latencies),
+        }
+    }
+}
+
+/// Sorts the values by decreasing count, the values with the same count are sorted
+/// alphabetically to keep the report stable.
+fn top_counts(counts: HashMap<&str, usize>, limit: usize) -> Vec<ValueCount> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_unstable_by(|(a, ca), (b, cb)| cb.cmp(ca).then_with(|| a.cmp(b)));
+    counts
+        .into_iter()
+        .take(limit)
+        .map(|(value, count)| ValueCount { value: value.to_string(), count })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueCount {
+    pub value: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LatencyPercentiles {
+    pub p50: usize,
+    pub p90: usize,
+    pub p95: usize,
+    pub p99: usize,
+}
+
+impl LatencyPercentiles {
+    /// Uses the nearest rank method, returns `None` if there is no latency.
+    fn from_sorted(latencies: &[usize]) -> Option<LatencyPercentiles> {
+        if latencies.is_empty() {
+            return None;
+        }
+
+        let percentile = |p: usize| {
+            let rank = (p * latencies.len() + 99) / 100;
+            latencies[rank.max(1) - 1]
+        };
+
+        Some(LatencyPercentiles {
+            p50: percentile(50),
+            p90: percentile(90),
+            p95: percentile(95),
+            p99: percentile(99),
+        })
+    }
+}
+
+/// Writes the recorded searches to the database at regular intervals, in a dedicated thread.
+pub fn schedule_persistence(search_analytics: Arc<SearchAnalytics>, db: Arc<Database>, interval: Duration) {
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+        if let Err(e) = search_analytics.persist(&db) {
+            error!("Unsuccessful search analytics write: {}", e);
+        }
+    });
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchAnalyticsReport {
+    pub number_of_searches: usize,
+    pub top_queries: Vec<ValueCount>,
+    pub top_zero_result_queries: Vec<ValueCount>,
+    pub top_filters: Vec<ValueCount>,
+    pub processing_time_ms: Option<LatencyPercentiles>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Duration;
+    use meilisearch_core::DatabaseOptions;
+    use tempfile::TempDir;
+
+    #[test]
+    fn report() {
+        let analytics = SearchAnalytics::new(6);
+        analytics.record("movies", SearchEvent::new(Some("Star  Wars"), 10, Vec::new(), 4, false));
+        analytics.record("movies", SearchEvent::new(Some("star wars"), 12, vec!["year > 2000".into()], 2, false));
+        analytics.record("movies", SearchEvent::new(Some("strar"), 0, Vec::new(), 1, false));
+        analytics.record("movies", SearchEvent::new(None, 20, vec!["year > 2000".into()], 3, false));
+        analytics.record("movies", SearchEvent::new(Some("alien"), 3, Vec::new(), 8, false));
+        analytics.record("movies", SearchEvent::new(Some("alien"), 3, Vec::new(), 0, true));
+        analytics.record("movies", SearchEvent::new(Some("alien"), 3, Vec::new(), 0, true));
+
+        // the oldest search has been dropped
+        let report = analytics.report("movies", Utc::now() - Duration::hours(1), 10);
+        assert_eq!(report.number_of_searches, 6);
+
+        let top: Vec<_> = report.top_queries.iter().map(|c| (c.value.as_str(), c.count)).collect();
+        assert_eq!(top, vec![("alien", 3), ("star wars", 1), ("strar", 1)]);
+
+        let zero: Vec<_> = report.top_zero_result_queries.iter().map(|c| c.value.as_str()).collect();
+        assert_eq!(zero, vec!["strar"]);
+        assert_eq!(report.top_filters[0].count, 2);
+
+        // the searches served by the cache are not part of the latencies
+        let latencies = report.processing_time_ms.unwrap();
+        assert_eq!(latencies.p50, 2);
+        assert_eq!(latencies.p99, 8);
+
+        let report = analytics.report("movies", Utc::now() + Duration::hours(1), 10);
+        assert_eq!(report.number_of_searches, 0);
+        assert_eq!(report.processing_time_ms, None);
+    }
+
+    #[test]
+    fn persist_and_load() {
+        let dir = TempDir::new().unwrap();
+        let db = Database::open_or_create(dir.path(), DatabaseOptions::default()).unwrap();
+        db.create_index("movies").unwrap();
+
+        let analytics = SearchAnalytics::new(10);
+        analytics.record("movies", SearchEvent::new(Some("alien"), 3, Vec::new(), 8, false));
+        analytics.persist(&db).unwrap();
+
+        let analytics = SearchAnalytics::new(10);
+        analytics.load(&db).unwrap();
+        let report = analytics.report("movies", Utc::now() - Duration::hours(1), 10);
+        assert_eq!(report.number_of_searches, 1);
+        assert_eq!(report.top_queries[0].value, "alien");
+
+        // the searches that can't be read are ignored
+        db.create_index("series").unwrap();
+        db.main_write::<_, _, Error>(|writer| {
+            let index = db.open_index("series").unwrap();
+            Ok(index.main.put_search_events(writer, b"not json")?)
+        }).unwrap();
+
+        let analytics = SearchAnalytics::new(10);
+        analytics.load(&db).unwrap();
+        let report = analytics.report("movies", Utc::now() - Duration::hours(1), 10);
+        assert_eq!(report.number_of_searches, 1);
+        let report = analytics.report("series", Utc::now() - Duration::hours(1), 10);
+        assert_eq!(report.number_of_searches, 0);
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+use crate::helpers::meilisearch::SearchResult;
+
+/// The results of the most recent searches, evicted in least recently used order once
+/// their size exceeds the limit. The size of a result is estimated from its JSON size.
+///
+/// The entries of an index are invalidated each time an update is processed on it, a search
+/// that started before the invalidation must therefore not be cached: each index has a
+/// generation that is incremented by the invalidation and checked before inserting.
+pub struct SearchCache {
+    max_size: usize,
+    inner: Mutex<SearchCacheInner>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Default)]
+struct SearchCacheInner {
+    entries: HashMap<(String, String), Entry>,
+    /// The keys of the entries sorted by their last use.
+    last_uses: BTreeMap<u64, (String, String)>,
+    generations: HashMap<String, u64>,
+    size: usize,
+    clock: u64,
+}
+
+struct Entry {
+    result: SearchResult,
+    size: usize,
+    last_use: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub number_of_entries: usize,
+    pub size: usize,
+}
+
+impl SearchCache {
+    pub fn new(max_size: usize) -> SearchCache {
+        SearchCache {
+            max_size,
+            inner: Mutex::new(SearchCacheInner::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get(&self, index_uid: &str, key: &str) -> Option<SearchResult> {
+        let mut inner = self.inner.lock().unwrap();
+        let inner = &mut *inner;
+        inner.clock += 1;
+
+        let entry_key = (index_uid.to_string(), key.to_string());
+        match inner.entries.get_mut(&entry_key) {
+            Some(entry) => {
+                inner.last_uses.remove(&entry.last_use);
+                entry.last_use = inner.clock;
+                inner.last_uses.insert(entry.last_use, entry_key);
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(entry.result.clone())
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Returns the current generation of the index, to give to `insert`.
+    pub fn generation(&self, index_uid: &str) -> u64 {
+        let inner = self.inner.lock().unwrap();
+        inner.generations.get(index_uid).copied().unwrap_or_default()
+    }
+
+    /// Caches the result unless the entries of the index have been invalidated since the
+    /// given generation was read, or the result alone is bigger than the cache.
+    pub fn insert(&self, index_uid: &str, generation: u64, key: String, result: &SearchResult) {
+        let size = match serde_json::to_vec(result) {
+            Ok(bytes) => bytes.len() + key.len(),
+            Err(_) => return,
+        };
+
+        if size > self.max_size {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        if inner.generations.get(index_uid).copied().unwrap_or_default() != generation {
+            return;
+        }
+
+        let entry_key = (index_uid.to_string(), key);
+        inner.remove(&entry_key);
+
+        while inner.size + size > self.max_size {
+            let oldest = match inner.last_uses.values().next() {
+                Some(key) => key.clone(),
+                None => break,
+            };
+            inner.remove(&oldest);
+        }
+
+        inner.clock += 1;
+        let last_use = inner.clock;
+        inner.size += size;
+        inner.last_uses.insert(last_use, entry_key.clone());
+        inner.entries.insert(entry_key, Entry { result: result.clone(), size, last_use });
+    }
+
+    /// Removes all the entries of the index.
+    pub fn invalidate(&self, index_uid: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        *inner.generations.entry(index_uid.to_string()).or_default() += 1;
+
+        let keys: Vec<_> = inner.entries.keys().filter(|(uid, _)| uid == index_uid).cloned().collect();
+        for key in keys {
+            inner.remove(&key);
+        }
+    }
+
+    pub fn stats(&self) -> SearchCacheStats {
+        let inner = self.inner.lock().unwrap();
+        SearchCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            number_of_entries: inner.entries.len(),
+            size: inner.size,
+        }
+    }
+}
+
+impl SearchCacheInner {
+    fn remove(&mut self, key: &(String, String)) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.last_uses.remove(&entry.last_use);
+            self.size -= entry.size;
+        }
+    }
+}
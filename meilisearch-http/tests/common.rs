@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 
-use actix_web::{http::StatusCode, test};
+use actix_web::{http::{HeaderMap, StatusCode}, test};
 use serde_json::{json, Value};
 use std::time::Duration;
 use tempdir::TempDir;
@@ -36,6 +36,16 @@ macro_rules! test_post_get_search {
     };
 }
 
+/// Returns the ids of the hits of a search response, in order.
+pub fn ids(response: &Value) -> Vec<u64> {
+    response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["id"].as_u64().unwrap())
+        .collect()
+}
+
 pub struct Server {
     pub uid: String,
     pub data: Data,
@@ -43,6 +53,10 @@ pub struct Server {
 
 impl Server {
     pub fn with_uid(uid: &str) -> Server {
+        Server::with_search_cache(uid, 0)
+    }
+
+    pub fn with_search_cache(uid: &str, search_cache_size: usize) -> Server {
         let tmp_dir = TempDir::new("meilisearch").unwrap();
 
         let default_db_options = DatabaseOptions::default();
@@ -58,6 +72,7 @@ impl Server {
             max_mdb_size: default_db_options.main_map_size,
             max_udb_size: default_db_options.update_map_size,
             http_payload_size_limit: 100000000,
+            search_cache_size,
             ..Opt::default()
         };
 
@@ -100,6 +115,20 @@ impl Server {
         server
     }
 
+    /// Creates the `test` index, with `id` as primary key, and adds the given documents.
+    pub async fn with_documents(documents: Value) -> Self {
+        let mut server = Self::with_uid("test");
+
+        let body = json!({
+            "uid": "test",
+            "primaryKey": "id",
+        });
+
+        server.create_index(body).await;
+        server.add_or_update_multiple_documents(documents).await;
+        server
+    }
+
     pub fn data(&self) -> &Data {
         &self.data
     }
@@ -123,6 +152,11 @@ impl Server {
     // Global Http request GET/POST/DELETE async or sync
 
     pub async fn get_request(&mut self, url: &str) -> (Value, StatusCode) {
+        let (response, status_code, _headers) = self.get_request_with_headers(url).await;
+        (response, status_code)
+    }
+
+    pub async fn get_request_with_headers(&mut self, url: &str) -> (Value, StatusCode, HeaderMap) {
         eprintln!("get_request: {}", url);
 
         let mut app =
@@ -131,10 +165,11 @@ impl Server {
         let req = test::TestRequest::get().uri(url).to_request();
         let res = test::call_service(&mut app, req).await;
         let status_code = res.status();
+        let headers = res.headers().clone();
 
         let body = test::read_body(res).await;
         let response = serde_json::from_slice(&body).unwrap_or_default();
-        (response, status_code)
+        (response, status_code, headers)
     }
 
     pub async fn post_request(&self, url: &str, body: Value) -> (Value, StatusCode) {
@@ -236,6 +271,14 @@ impl Server {
         self.get_request(&url).await
     }
 
+    pub async fn federated_search(&mut self, body: Value) -> (Value, StatusCode) {
+        self.post_request("/indexes/search", body).await
+    }
+
+    pub async fn multi_search(&mut self, body: Value) -> (Value, StatusCode) {
+        self.post_request("/multi-search", body).await
+    }
+
     pub async fn get_index(&mut self) -> (Value, StatusCode) {
         let url = format!("/indexes/{}", self.uid);
         self.get_request(&url).await
@@ -450,6 +493,61 @@ impl Server {
         self.delete_request_async(&url).await
     }
 
+    pub async fn get_sortable_attributes(&mut self) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/settings/sortable-attributes", self.uid);
+        self.get_request(&url).await
+    }
+
+    pub async fn update_sortable_attributes(&mut self, body: Value) {
+        let url = format!("/indexes/{}/settings/sortable-attributes", self.uid);
+        self.post_request_async(&url, body).await;
+    }
+
+    pub async fn delete_sortable_attributes(&mut self) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/settings/sortable-attributes", self.uid);
+        self.delete_request_async(&url).await
+    }
+
+    pub async fn get_typo_tolerance(&mut self) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/settings/typo-tolerance", self.uid);
+        self.get_request(&url).await
+    }
+
+    pub async fn update_typo_tolerance(&mut self, body: Value) {
+        let url = format!("/indexes/{}/settings/typo-tolerance", self.uid);
+        self.post_request_async(&url, body).await;
+    }
+
+    pub async fn update_typo_tolerance_sync(&mut self, body: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/settings/typo-tolerance", self.uid);
+        self.post_request(&url, body).await
+    }
+
+    pub async fn delete_typo_tolerance(&mut self) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/settings/typo-tolerance", self.uid);
+        self.delete_request_async(&url).await
+    }
+
+    pub async fn get_query_rules(&mut self) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/settings/query-rules", self.uid);
+        self.get_request(&url).await
+    }
+
+    pub async fn update_query_rules(&mut self, body: Value) {
+        let url = format!("/indexes/{}/settings/query-rules", self.uid);
+        self.post_request_async(&url, body).await;
+    }
+
+    pub async fn update_query_rules_sync(&mut self, body: Value) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/settings/query-rules", self.uid);
+        self.post_request(&url, body).await
+    }
+
+    pub async fn delete_query_rules(&mut self) -> (Value, StatusCode) {
+        let url = format!("/indexes/{}/settings/query-rules", self.uid);
+        self.delete_request_async(&url).await
+    }
+
     pub async fn get_synonyms(&mut self) -> (Value, StatusCode) {
         let url = format!("/indexes/{}/settings/synonyms", self.uid);
         self.get_request(&url).await
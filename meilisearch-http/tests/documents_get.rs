@@ -21,3 +21,31 @@ async fn get_empty_documents_list() {
     assert_eq!(status, StatusCode::OK);
     assert!(response.as_array().unwrap().is_empty());
 }
+
+#[actix_rt::test]
+async fn get_documents_with_cursor() {
+    let mut server = common::Server::test_server().await;
+
+    let (response, _status_code) = server.get_request("/indexes/test/documents?limit=1000").await;
+    let expected: Vec<_> = response
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|document| document["id"].clone())
+        .collect();
+
+    // walk through all the pages, each one starts after the last document of the previous one
+    let mut ids = Vec::new();
+    let mut url = "/indexes/test/documents?limit=10".to_string();
+    loop {
+        let (response, status, headers) = server.get_request_with_headers(&url).await;
+        assert_eq!(status, StatusCode::OK);
+        ids.extend(response.as_array().unwrap().iter().map(|document| document["id"].clone()));
+        match headers.get("X-Meili-Next-Cursor") {
+            Some(cursor) => url = format!("/indexes/test/documents?limit=10&cursor={}", cursor.to_str().unwrap()),
+            None => break,
+        }
+    }
+
+    assert_eq!(ids, expected);
+}
@@ -295,7 +295,18 @@ async fn dump_index_settings_should_be_valid() {
             "gender",
             "color",
             "tags"
-        ]
+        ],
+        "sortableAttributes": [],
+        "queryRules": [],
+        "typoTolerance": {
+            "enabled": true,
+            "minWordSizeForTypos": {
+                "oneTypo": 5,
+                "twoTypos": 9
+            },
+            "disableOnWords": [],
+            "disableOnAttributes": []
+        }
     });
 
     server.update_all_settings(expected.clone()).await;
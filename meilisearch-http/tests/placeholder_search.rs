@@ -623,7 +623,44 @@ async fn test_filter_nb_hits_search_placeholder() {
     let (response, _) = server.search_post(json!({})).await;
     assert_eq!(response["nbHits"], 2);
 
+    // the page is filled with the documents accepted by the distinct rule
+    let (response, _) = server.search_post(json!({"limit": 2})).await;
+    assert_eq!(response["hits"].as_array().unwrap().len(), 2);
+
     let (response, _) = server.search_post(json!({"filters": "size < 3"})).await;
     println!("result: {}", response);
     assert_eq!(response["nbHits"], 1);
 }
+
+#[actix_rt::test]
+async fn placeholder_search_with_cursor() {
+    let mut server = common::Server::test_server().await;
+
+    let body = json!({
+        "rankingRules": ["asc(age)"],
+    });
+    server.update_all_settings(body).await;
+
+    let (response, _status_code) = server.search_post(json!({ "limit": 1000 })).await;
+    let expected: Vec<_> = response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["id"].clone())
+        .collect();
+
+    // walk through all the pages, each one starts after the last hit of the previous one
+    let mut ids = Vec::new();
+    let mut query = json!({ "limit": 7 });
+    loop {
+        let (response, status_code) = server.search_post(query).await;
+        assert_eq!(status_code, 200);
+        ids.extend(response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].clone()));
+        match response["nextCursor"].as_str() {
+            Some(cursor) => query = json!({ "limit": 7, "cursor": cursor }),
+            None => break,
+        }
+    }
+
+    assert_eq!(ids, expected);
+}
@@ -128,7 +128,7 @@ async fn search_unexpected_params() {
 
     let query = json! ({"lol": "unexpected"});
 
-    let expected = "unknown field `lol`, expected one of `q`, `offset`, `limit`, `attributesToRetrieve`, `attributesToCrop`, `cropLength`, `attributesToHighlight`, `filters`, `matches`, `facetFilters`, `facetsDistribution` at line 1 column 6";
+    let expected = "unknown field `lol`, expected one of `q`, `offset`, `limit`, `attributesToRetrieve`, `attributesToCrop`, `cropLength`, `attributesToHighlight`, `filters`, `matches`, `facetFilters`, `facetsDistribution`, `sort`, `showRankingInfo`, `highlightPreTag`, `highlightPostTag`, `cropMarker`, `cropSnippets`, `attributesToSearchOn`, `timeoutMs`, `cursor`, `exhaustiveNbHits`, `exhaustiveNbHitsCap`, `matchingStrategy`, `autoCorrect`, `vector`, `semanticRatio`, `rankingRules`, `distinct`, `distinctCount` at line 1 column 6";
 
     let post_query = serde_json::from_str::<meilisearch_http::routes::search::SearchQueryPost>(&query.to_string());
     assert!(post_query.is_err());
@@ -1945,3 +1945,594 @@ async fn test_filter_nb_hits_search_normal() {
     println!("result: {}", response);
     assert_eq!(response["nbHits"], 1);
 }
+
+#[actix_rt::test]
+async fn search_with_sort() {
+    let mut server = common::Server::with_uid("test");
+
+    let body = json!({
+        "uid": "test",
+        "primaryKey": "id",
+    });
+
+    server.create_index(body).await;
+    let documents = json!([
+        { "id": 1, "content": "a", "size": 2, "rank": 3 },
+        { "id": 2, "content": "a", "size": 3, "rank": 1 },
+        { "id": 3, "content": "a", "size": 1, "rank": 2 },
+    ]);
+
+    server.update_sortable_attributes(json!(["size", "rank"])).await;
+    server.add_or_update_multiple_documents(documents).await;
+
+    let ids = |response: &Value| -> Vec<u64> {
+        response["hits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hit| hit["id"].as_u64().unwrap())
+            .collect()
+    };
+
+    let query = json!({ "q": "a", "sort": ["asc(size)"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(ids(&response), vec![3, 1, 2]);
+    });
+
+    let query = json!({ "q": "a", "sort": ["desc(rank)"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(ids(&response), vec![1, 3, 2]);
+    });
+
+    // placeholder search
+    let query = json!({ "sort": ["desc(size)"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(ids(&response), vec![2, 1, 3]);
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_invalid_sort() {
+    let mut server = common::Server::test_server().await;
+
+    let query = json!({ "q": "a", "sort": ["age"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+
+    // the attribute isn't sortable
+    let query = json!({ "q": "a", "sort": ["asc(age)"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_ranking_rules() {
+    let mut server = common::Server::with_uid("test");
+
+    let body = json!({
+        "uid": "test",
+        "primaryKey": "id",
+    });
+
+    server.create_index(body).await;
+    let documents = json!([
+        { "id": 1, "content": "a", "size": 2, "rank": 3 },
+        { "id": 2, "content": "a", "size": 3, "rank": 1 },
+        { "id": 3, "content": "a", "size": 1, "rank": 2 },
+    ]);
+
+    server.update_sortable_attributes(json!(["size", "rank"])).await;
+    server.add_or_update_multiple_documents(documents).await;
+
+    let ids = |response: &Value| -> Vec<u64> {
+        response["hits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|hit| hit["id"].as_u64().unwrap())
+            .collect()
+    };
+
+    let query = json!({ "q": "a", "rankingRules": ["words", "desc(rank)"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(ids(&response), vec![1, 3, 2]);
+    });
+
+    // placeholder search
+    let query = json!({ "rankingRules": ["typo", "asc(size)"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(ids(&response), vec![3, 1, 2]);
+    });
+
+    // the sort replaces the custom rules of the given ranking rules
+    let query = json!({ "q": "a", "rankingRules": ["words", "desc(rank)"], "sort": ["asc(size)"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(ids(&response), vec![3, 1, 2]);
+    });
+
+    // the ranking rules of the index are untouched
+    let (response, status_code) = server.get_ranking_rules().await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response, json!(["typo", "words", "proximity", "attribute", "wordsPosition", "exactness"]));
+
+    let query = json!({ "q": "a", "rankingRules": ["words", "unknown"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+
+    // the attribute isn't sortable
+    let query = json!({ "q": "a", "rankingRules": ["asc(content)"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+
+    let query = json!({ "q": "a", "rankingRules": ["words", "desc(rank)", "words"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+
+    let (response, status_code) = server.search_post(json!({ "q": "a", "rankingRules": [] })).await;
+    assert_eq!(status_code, 400);
+    assert_eq!(response["errorCode"], "bad_parameter");
+}
+
+#[actix_rt::test]
+async fn search_with_distinct() {
+    let mut server = common::Server::with_uid("test");
+
+    let body = json!({
+        "uid": "test",
+        "primaryKey": "id",
+    });
+
+    server.create_index(body).await;
+    let documents = json!([
+        { "id": 1, "content": "a", "brand": "x", "color": "red" },
+        { "id": 2, "content": "a", "brand": "x", "color": "blue" },
+        { "id": 3, "content": "a", "brand": "x", "color": "red" },
+        { "id": 4, "content": "a", "brand": "y", "color": "red" },
+    ]);
+
+    server.add_or_update_multiple_documents(documents).await;
+    server.update_distinct_attribute(json!("color")).await;
+
+    let nb_hits = |response: &Value| response["hits"].as_array().unwrap().len();
+
+    let query = json!({ "q": "a" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(nb_hits(&response), 2);
+    });
+
+    // the distinct attribute of the query replaces the one of the index
+    let query = json!({ "q": "a", "distinct": "brand" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(nb_hits(&response), 2);
+    });
+
+    let query = json!({ "q": "a", "distinct": "brand", "distinctCount": 2 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(nb_hits(&response), 3);
+    });
+
+    // the distinct attribute of the index is used with the count of the query
+    let query = json!({ "q": "a", "distinctCount": 3 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(nb_hits(&response), 4);
+    });
+
+    let query = json!({ "q": "a", "distinct": "unknown" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+
+    let query = json!({ "q": "a", "distinct": "brand", "distinctCount": 0 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_phrase() {
+    let mut server = common::Server::test_server().await;
+
+    let query = json!({
+        "q": "\"exercitation quis\"",
+        "filters": "name='Lucas Hess'",
+        "attributesToHighlight": ["about"],
+    });
+
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let hits = response["hits"].as_array().unwrap();
+        assert_eq!(hits.len(), 1);
+        let about = hits[0]["_formatted"]["about"].as_str().unwrap();
+        assert!(about.contains("<em>exercitation quis</em>"));
+    });
+
+    // the words are both present but not next to each other
+    let query = json!({
+        "q": "\"quis exercitation\"",
+        "filters": "name='Lucas Hess'",
+    });
+
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["hits"].as_array().unwrap().len(), 0);
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_excluded_word() {
+    let mut server = common::Server::test_server().await;
+
+    let query = json!({
+        "q": "exercitation",
+        "filters": "name='Lucas Hess'",
+    });
+
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["hits"].as_array().unwrap().len(), 1);
+    });
+
+    let query = json!({
+        "q": "exercitation -quis",
+        "filters": "name='Lucas Hess'",
+    });
+
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["hits"].as_array().unwrap().len(), 0);
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_ranking_info() {
+    let mut server = common::Server::with_uid("test");
+
+    let body = json!({
+        "uid": "test",
+        "primaryKey": "id",
+    });
+
+    server.create_index(body).await;
+    let documents = json!([
+        { "id": 1, "content": "hello world", "rank": 3 },
+        { "id": 2, "content": "hello big world", "rank": 1 },
+    ]);
+
+    server.update_sortable_attributes(json!(["rank"])).await;
+    server.add_or_update_multiple_documents(documents).await;
+
+    let query = json!({ "q": "hello world", "sort": ["asc(rank)"], "showRankingInfo": true });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let hits = response["hits"].as_array().unwrap();
+        assert_eq!(hits.len(), 2);
+
+        assert_eq!(hits[0]["id"], 1);
+        let ranking_info = hits[0]["_rankingInfo"].as_object().unwrap();
+        assert_eq!(ranking_info.len(), 7);
+        assert_eq!(ranking_info["typo"], 0);
+        assert_eq!(ranking_info["words"], 2);
+        assert_eq!(ranking_info["proximity"], 1);
+        assert_eq!(ranking_info["asc(rank)"], 3);
+
+        assert_eq!(hits[1]["id"], 2);
+        let ranking_info = hits[1]["_rankingInfo"].as_object().unwrap();
+        assert_eq!(ranking_info["proximity"], 2);
+        assert_eq!(ranking_info["asc(rank)"], 1);
+    });
+
+    // the ranking infos are opt-in
+    let query = json!({ "q": "hello world" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert!(response["hits"][0].get("_rankingInfo").is_none());
+    });
+
+    // placeholder search is only ranked by the custom rules
+    let query = json!({ "sort": ["asc(rank)"], "showRankingInfo": true });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["hits"][0]["_rankingInfo"], json!({ "asc(rank)": 1 }));
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_custom_highlight_tags_and_crop_marker() {
+    let mut server = common::Server::test_server().await;
+
+    let query = json!({
+        "q": "exercitation",
+        "filters": "name='Lucas Hess'",
+        "attributesToHighlight": ["about"],
+        "attributesToCrop": ["about"],
+        "cropLength": 5,
+        "highlightPreTag": "<b>",
+        "highlightPostTag": "</b>",
+        "cropMarker": "…",
+    });
+
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let about = response["hits"][0]["_formatted"]["about"].as_str().unwrap();
+        assert!(about.starts_with("…"));
+        assert!(about.ends_with("…"));
+        assert!(about.contains("<b>exercitation</b>"));
+        assert!(!about.contains("<em>"));
+    });
+}
+
+#[actix_rt::test]
+async fn search_highlight_array_values() {
+    let mut server = common::Server::test_server().await;
+
+    let query = json!({
+        "q": "bug",
+        "filters": "name='Lucas Hess'",
+        "attributesToHighlight": ["tags"],
+    });
+
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["hits"][0]["_formatted"]["tags"], json!(["<em>bug</em>", "<em>bug</em>"]));
+    });
+}
+
+#[actix_rt::test]
+async fn search_crop_best_window_snippets() {
+    let mut server = common::Server::with_uid("test");
+
+    let body = json!({
+        "uid": "test",
+        "primaryKey": "id",
+    });
+
+    server.create_index(body).await;
+    let documents = json!([
+        {
+            "id": 1,
+            "body": "rust is a language. here are some filler words to separate the passages. rust for the web is nice",
+        },
+    ]);
+    server.add_or_replace_multiple_documents(documents).await;
+
+    let query = json!({
+        "q": "rust web",
+        "attributesToHighlight": ["body"],
+        "attributesToCrop": ["body"],
+        "cropLength": 10,
+        "cropMarker": "…",
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let body = response["hits"][0]["_formatted"]["body"].as_str().unwrap();
+        assert!(body.contains("<em>rust</em> for the <em>web</em>"));
+        assert!(!body.contains("language"));
+    });
+
+    let query = json!({
+        "q": "rust web",
+        "attributesToHighlight": ["body"],
+        "attributesToCrop": ["body"],
+        "cropLength": 10,
+        "cropMarker": "…",
+        "cropSnippets": 2,
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let body = response["hits"][0]["_formatted"]["body"].as_str().unwrap();
+        assert!(body.starts_with("<em>rust</em> is a…"));
+        assert!(body.contains("<em>rust</em> for the <em>web</em>"));
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_attributes_to_search_on() {
+    let mut server = common::Server::test_server().await;
+
+    let query = json!({
+        "q": "lucas",
+        "attributesToSearchOn": ["name"],
+        "matches": true,
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let hits = response["hits"].as_array().unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0]["name"], "Lucas Hess");
+        // the email also matches but isn't searched on
+        let matches_info = hits[0]["_matchesInfo"].as_object().unwrap();
+        assert_eq!(matches_info.keys().collect::<Vec<_>>(), ["name"]);
+    });
+
+    let query = json!({ "q": "lucas", "attributesToSearchOn": ["about"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["hits"].as_array().unwrap().len(), 0);
+        assert_eq!(response["nbHits"], 0);
+    });
+
+    let query = json!({ "q": "lucas", "attributesToSearchOn": ["unknown"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_timeout() {
+    let mut server = common::Server::test_server().await;
+
+    let query = json!({ "q": "exercitation" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["partial"], false);
+        assert_eq!(response["exhaustiveNbHits"], true);
+    });
+
+    // the budget is exhausted right away, the results are not sorted but still counted
+    let query = json!({ "q": "exercitation", "timeoutMs": 0 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["partial"], true);
+        assert_eq!(response["exhaustiveNbHits"], true);
+        assert!(!response["hits"].as_array().unwrap().is_empty());
+    });
+
+    let query = json!({ "q": "exercitation", "timeoutMs": 3_600_000 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_cursor() {
+    let mut server = common::Server::test_server().await;
+
+    let (response, _status_code) = server.search_post(json!({ "q": "exercitation", "limit": 1000 })).await;
+    let expected: Vec<_> = response["hits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["id"].clone())
+        .collect();
+
+    // walk through all the pages, each one starts after the last hit of the previous one
+    let mut ids = Vec::new();
+    let mut query = json!({ "q": "exercitation", "limit": 5 });
+    loop {
+        let (response, status_code) = server.search_post(query).await;
+        assert_eq!(status_code, 200);
+        ids.extend(response["hits"].as_array().unwrap().iter().map(|hit| hit["id"].clone()));
+        match response["nextCursor"].as_str() {
+            Some(cursor) => query = json!({ "q": "exercitation", "limit": 5, "cursor": cursor }),
+            None => break,
+        }
+    }
+
+    assert_eq!(ids, expected);
+
+    // the page starts after the cursor, it can't be combined with an offset
+    let (response, _status_code) = server.search_post(json!({ "q": "exercitation", "limit": 5 })).await;
+    let cursor = response["nextCursor"].as_str().unwrap();
+    let query = json!({ "q": "exercitation", "offset": 5, "cursor": cursor });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+
+    let query = json!({ "q": "exercitation", "cursor": "not a cursor" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_exhaustive_nb_hits() {
+    let mut server = common::Server::test_server().await;
+
+    let query = json!({ "q": "exercitation", "filters": "color='blue'", "limit": 1000 });
+    let (response, _status_code) = server.search_post(query).await;
+    let expected = response["hits"].as_array().unwrap().len();
+    assert!(expected > 2);
+
+    let query = json!({ "q": "exercitation", "filters": "color='blue'", "limit": 1, "exhaustiveNbHits": true });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbHits"], expected);
+        assert_eq!(response["exhaustiveNbHits"], true);
+    });
+
+    // the count is exact up to the cap
+    let query = json!({ "q": "exercitation", "filters": "color='blue'", "limit": 1, "exhaustiveNbHitsCap": 2 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbHits"], 2);
+        assert_eq!(response["exhaustiveNbHits"], false);
+    });
+
+    let query = json!({ "q": "exercitation", "filters": "color='blue'", "limit": 1, "exhaustiveNbHitsCap": 1000 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbHits"], expected);
+        assert_eq!(response["exhaustiveNbHits"], true);
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_matching_strategy() {
+    let mut server = common::Server::test_server().await;
+
+    let query = json!({ "q": "exercitation qwxzvbk", "matchingStrategy": "all" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbHits"], 0);
+    });
+
+    // the last word doesn't match any document, it is dropped
+    let query = json!({ "q": "exercitation qwxzvbk", "matchingStrategy": "last" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert!(response["nbHits"].as_u64().unwrap() > 0);
+    });
+
+    let query = json!({ "q": "exercitation", "matchingStrategy": "first" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_auto_correct() {
+    let mut server = common::Server::test_server().await;
+
+    // the misspelled word matches nothing, a correction is suggested
+    let query = json!({ "q": "sumt" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(response["nbHits"], 0);
+        assert_eq!(response["suggestedQuery"], "sunt");
+        assert!(response.get("queryCorrected").is_none());
+    });
+
+    let query = json!({ "q": "sumt", "autoCorrect": true });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert!(response["nbHits"].as_u64().unwrap() > 0);
+        assert_eq!(response["query"], "sumt");
+        assert_eq!(response["suggestedQuery"], "sunt");
+        assert_eq!(response["queryCorrected"], true);
+    });
+
+    // every word of the query is known, nothing is suggested
+    let query = json!({ "q": "sunt", "autoCorrect": true });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert!(response.get("suggestedQuery").is_none());
+        assert!(response.get("queryCorrected").is_none());
+    });
+}
@@ -0,0 +1,41 @@
+use serde_json::json;
+
+mod common;
+
+#[actix_rt::test]
+async fn search_analytics_report() {
+    let mut server = common::Server::test_server().await;
+
+    server.search_post(json!({ "q": "Exercitation" })).await;
+    server.search_post(json!({ "q": "exercitation ", "filters": "gender='male'" })).await;
+    server.search_get("q=zzzzzzzz").await;
+    server.search_post(json!({ "filters": "gender='male'" })).await;
+
+    let (response, status_code) = server.get_request("/indexes/test/stats/search").await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response["numberOfSearches"], 4);
+    assert_eq!(response["topQueries"], json!([
+        { "value": "exercitation", "count": 2 },
+        { "value": "zzzzzzzz", "count": 1 },
+    ]));
+    assert_eq!(response["topZeroResultQueries"], json!([{ "value": "zzzzzzzz", "count": 1 }]));
+    assert_eq!(response["topFilters"], json!([{ "value": "gender='male'", "count": 2 }]));
+    assert!(response["processingTimeMs"]["p50"].is_u64());
+
+    let (response, status_code) = server.get_request("/indexes/test/stats/search?limit=1").await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response["topQueries"], json!([{ "value": "exercitation", "count": 2 }]));
+
+    let (response, status_code) = server.get_request("/indexes/test/stats/search?windowSec=3600").await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response["numberOfSearches"], 4);
+}
+
+#[actix_rt::test]
+async fn search_analytics_on_unknown_index() {
+    let mut server = common::Server::with_uid("test");
+
+    let (response, status_code) = server.get_request("/indexes/test/stats/search").await;
+    assert_eq!(status_code, 404);
+    assert_eq!(response["errorCode"], "index_not_found");
+}
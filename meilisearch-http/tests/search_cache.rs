@@ -0,0 +1,52 @@
+use serde_json::json;
+
+mod common;
+
+#[actix_rt::test]
+async fn search_cache_invalidated_by_updates() {
+    let mut server = common::Server::with_search_cache("test", 1024 * 1024);
+
+    let body = json!({
+        "uid": "test",
+        "primaryKey": "id",
+    });
+
+    server.create_index(body).await;
+    server.add_or_update_multiple_documents(json!([{ "id": 1, "title": "hello world" }])).await;
+
+    let (response, status_code) = server.search_post(json!({ "q": "hello" })).await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response["nbHits"], 1);
+
+    // the same query typed differently is served from the cache
+    let (response, status_code) = server.search_post(json!({ "q": "Hello " })).await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response["nbHits"], 1);
+    assert_eq!(response["query"], "Hello ");
+
+    let (response, _) = server.get_request("/stats").await;
+    assert_eq!(response["searchCache"]["hits"], 1);
+    assert_eq!(response["searchCache"]["misses"], 1);
+    assert_eq!(response["searchCache"]["numberOfEntries"], 1);
+
+    server.add_or_update_multiple_documents(json!([{ "id": 2, "title": "hello there" }])).await;
+
+    let (response, status_code) = server.search_post(json!({ "q": "hello" })).await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response["nbHits"], 2);
+
+    let (response, _) = server.get_request("/stats").await;
+    assert_eq!(response["searchCache"]["hits"], 1);
+    assert_eq!(response["searchCache"]["misses"], 2);
+}
+
+#[actix_rt::test]
+async fn search_cache_disabled_by_default() {
+    let mut server = common::Server::test_server().await;
+
+    server.search_post(json!({ "q": "exercitation" })).await;
+
+    let (response, status_code) = server.get_request("/stats").await;
+    assert_eq!(status_code, 200);
+    assert!(response.get("searchCache").is_none());
+}
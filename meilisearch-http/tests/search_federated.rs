@@ -0,0 +1,105 @@
+use serde_json::{json, Value};
+
+mod common;
+
+async fn add_second_index(server: &mut common::Server) {
+    let body = json!({
+        "uid": "articles",
+        "primaryKey": "id",
+    });
+    server.create_index(body).await;
+
+    let documents = json!([
+        { "id": 1, "title": "Exercitation in the wild", "category": "nature" },
+        { "id": 2, "title": "Exercitation for beginners", "category": "sport" },
+        { "id": 3, "title": "Nothing to see here", "category": "sport" },
+    ]);
+    server.post_request_async("/indexes/articles/documents", documents).await;
+}
+
+#[actix_rt::test]
+async fn federated_search_labels_hits_with_their_index() {
+    let mut server = common::Server::test_server().await;
+    add_second_index(&mut server).await;
+
+    let query = json!({
+        "q": "exercitation",
+        "limit": 50,
+        "indexes": [
+            { "indexUid": "test" },
+            { "indexUid": "articles" },
+        ],
+    });
+
+    let (response, status_code) = server.federated_search(query).await;
+    assert_eq!(status_code, 200);
+
+    let hits = response["hits"].as_array().unwrap();
+    assert!(hits.iter().any(|hit| hit["_indexUid"] == "test"));
+    assert_eq!(hits.iter().filter(|hit| hit["_indexUid"] == "articles").count(), 2);
+}
+
+#[actix_rt::test]
+async fn federated_search_weights_and_filters() {
+    let mut server = common::Server::test_server().await;
+    add_second_index(&mut server).await;
+
+    let query = json!({
+        "q": "exercitation",
+        "limit": 3,
+        "indexes": [
+            { "indexUid": "test" },
+            { "indexUid": "articles", "weight": 10.0, "filters": "category = sport" },
+        ],
+    });
+
+    let (response, status_code) = server.federated_search(query).await;
+    assert_eq!(status_code, 200);
+
+    let hits = response["hits"].as_array().unwrap();
+    assert_eq!(hits.len(), 3);
+    assert_eq!(hits[0]["_indexUid"], "articles");
+    assert_eq!(hits[0]["id"], 2);
+    assert!(hits[1..].iter().all(|hit| hit["_indexUid"] == "test"));
+}
+
+#[actix_rt::test]
+async fn federated_search_bad_requests() {
+    let mut server = common::Server::test_server().await;
+
+    let (_response, status_code) = server.federated_search(json!({ "q": "exercitation", "indexes": [] })).await;
+    assert_eq!(status_code, 400);
+
+    let query = json!({
+        "q": "exercitation",
+        "indexes": [{ "indexUid": "test", "weight": 0 }],
+    });
+    let (_response, status_code) = server.federated_search(query).await;
+    assert_eq!(status_code, 400);
+
+    // the page must end before the bound of the hits fetched from each index
+    let query = json!({
+        "q": "exercitation",
+        "offset": 1_000_000_000,
+        "indexes": [{ "indexUid": "test" }],
+    });
+    let (_response, status_code) = server.federated_search(query).await;
+    assert_eq!(status_code, 400);
+
+    let query = json!({
+        "q": "exercitation",
+        "offset": usize::max_value(),
+        "limit": 20,
+        "indexes": [{ "indexUid": "test" }],
+    });
+    let (_response, status_code) = server.federated_search(query).await;
+    assert_eq!(status_code, 400);
+
+    let query = json!({
+        "q": "exercitation",
+        "indexes": [{ "indexUid": "unknown" }],
+    });
+    let (response, status_code) = server.federated_search(query).await;
+    assert_eq!(status_code, 404);
+    assert_eq!(response["errorCode"], Value::from("index_not_found"));
+}
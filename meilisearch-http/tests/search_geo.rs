@@ -0,0 +1,68 @@
+use serde_json::json;
+
+mod common;
+
+async fn geo_server() -> common::Server {
+    let documents = json!([
+        { "id": 1, "name": "restaurant du louvre", "_geo": { "lat": 48.8606, "lng": 2.3376 } },
+        { "id": 2, "name": "restaurant de la tour", "_geo": { "lat": "48.8584", "lng": "2.2945" } },
+        { "id": 3, "name": "london restaurant", "_geo": { "lat": 51.5074, "lng": -0.1278 } },
+        { "id": 4, "name": "restaurant somewhere" },
+    ]);
+
+    common::Server::with_documents(documents).await
+}
+
+#[actix_rt::test]
+async fn search_with_geo_filters() {
+    let mut server = geo_server().await;
+
+    // within 10km of the center of Paris
+    let query = json!({ "q": "restaurant", "filters": "_geoRadius(48.8566, 2.3522, 10000)" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let mut ids = common::ids(&response);
+        ids.sort_unstable();
+        assert_eq!(ids, vec![1, 2]);
+    });
+
+    let query = json!({ "filters": "_geoBoundingBox([52, 1], [51, -1])" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::ids(&response), vec![3]);
+    });
+
+    let query = json!({ "q": "restaurant", "filters": "_geoRadius(148.8566, 2.3522, 10000)" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "invalid_filter");
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_geo_sort() {
+    let mut server = geo_server().await;
+
+    // the documents without a location come last
+    let query = json!({ "q": "restaurant", "sort": ["_geoPoint(51.5, -0.12)"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::ids(&response), vec![3, 2, 1, 4]);
+        assert!(response["hits"][0]["_geoDistance"].as_u64().unwrap() < 2_000);
+        assert!(response["hits"][3].get("_geoDistance").is_none());
+    });
+
+    // placeholder search
+    let query = json!({ "sort": ["_geoPoint(48.8606, 2.3376)"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::ids(&response), vec![1, 2, 3, 4]);
+        assert_eq!(response["hits"][0]["_geoDistance"], 0);
+    });
+
+    let query = json!({ "q": "restaurant", "sort": ["_geoPoint(0)"] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+}
@@ -0,0 +1,63 @@
+use serde_json::json;
+
+mod common;
+
+#[actix_rt::test]
+async fn multi_search_returns_results_in_order() {
+    let mut server = common::Server::test_server().await;
+
+    let queries = json!([
+        { "indexUid": "test", "q": "exercitation", "limit": 3 },
+        { "indexUid": "test", "q": "", "limit": 1, "filters": "gender='male'" },
+    ]);
+
+    let (response, status_code) = server.multi_search(queries).await;
+    assert_eq!(status_code, 200);
+
+    let results = response.as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["query"], "exercitation");
+    assert_eq!(results[0]["hits"].as_array().unwrap().len(), 3);
+    assert_eq!(results[1]["limit"], 1);
+    assert_eq!(results[1]["hits"][0]["gender"], "male");
+}
+
+#[actix_rt::test]
+async fn multi_search_errors_do_not_fail_the_batch() {
+    let mut server = common::Server::test_server().await;
+
+    let queries = json!([
+        { "indexUid": "unknown", "q": "exercitation" },
+        { "indexUid": "test", "q": "exercitation", "limit": 1 },
+    ]);
+
+    let (response, status_code) = server.multi_search(queries).await;
+    assert_eq!(status_code, 200);
+
+    let results = response.as_array().unwrap();
+    assert_eq!(results[0]["error"]["errorCode"], "index_not_found");
+    assert_eq!(results[1]["hits"].as_array().unwrap().len(), 1);
+}
+
+#[actix_rt::test]
+async fn multi_search_unknown_field() {
+    let mut server = common::Server::test_server().await;
+
+    // a malformed query is rejected like on the search route, without failing the batch
+    let queries = json!([
+        { "indexUid": "test", "lol": "exercitation" },
+        { "indexUid": "test", "q": "exercitation", "limt": 1 },
+        { "q": "exercitation" },
+        { "indexUid": "test", "q": "exercitation", "limit": 1 },
+    ]);
+
+    let (response, status_code) = server.multi_search(queries).await;
+    assert_eq!(status_code, 200);
+
+    let results = response.as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    for result in &results[..3] {
+        assert_eq!(result["error"]["errorCode"], "bad_request");
+    }
+    assert_eq!(results[3]["hits"].as_array().unwrap().len(), 1);
+}
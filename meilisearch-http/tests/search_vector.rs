@@ -0,0 +1,86 @@
+use serde_json::json;
+
+mod common;
+
+async fn vector_server() -> common::Server {
+    let documents = json!([
+        { "id": 1, "name": "red apple", "color": "red", "_vector": [1, 0, 0] },
+        { "id": 2, "name": "green apple", "color": "green", "_vector": [0, 1, 0] },
+        { "id": 3, "name": "red cherry", "color": "red", "_vector": [0.9, 0.1, 0] },
+        { "id": 4, "name": "yellow banana", "color": "yellow" },
+    ]);
+
+    common::Server::with_documents(documents).await
+}
+
+#[actix_rt::test]
+async fn search_with_vector() {
+    let mut server = vector_server().await;
+
+    // the documents without embedding are not returned
+    let query = json!({ "vector": [1, 0, 0] });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::ids(&response), vec![1, 3, 2]);
+        assert_eq!(response["nbHits"], 3);
+    });
+
+    let query = json!({ "vector": [1, 0, 0], "filters": "color = green" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::ids(&response), vec![2]);
+    });
+
+    let query = json!({ "vector": [1, 0, 0], "offset": 1, "limit": 1 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::ids(&response), vec![3]);
+        assert_eq!(response["nbHits"], 3);
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_vector_and_facets() {
+    let mut server = vector_server().await;
+
+    let body = json!({ "attributesForFaceting": ["color"] });
+    server.update_all_settings(body).await;
+
+    let query = json!({
+        "vector": [0, 1, 0],
+        "facetFilters": ["color:red"],
+        "facetsDistribution": ["color"],
+    });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::ids(&response), vec![3, 1]);
+        assert_eq!(response["facetsDistribution"]["color"]["red"], 2);
+    });
+}
+
+#[actix_rt::test]
+async fn hybrid_search() {
+    let mut server = vector_server().await;
+
+    // the semantic ratio favors the similarity over the keyword ranking
+    let query = json!({ "q": "apple", "vector": [0, 1, 0], "semanticRatio": 0.9 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::ids(&response), vec![2, 1, 3]);
+    });
+
+    // without semantic ratio only the keywords are ranked
+    let query = json!({ "q": "apple", "vector": [0, 1, 0], "semanticRatio": 0 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let mut ids = common::ids(&response);
+        ids.sort_unstable();
+        assert_eq!(ids, vec![1, 2]);
+    });
+
+    let query = json!({ "q": "apple", "vector": [0, 1, 0], "semanticRatio": 2 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+}
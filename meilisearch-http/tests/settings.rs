@@ -48,6 +48,17 @@ async fn write_all_and_delete() {
             "street": ["avenue"],
         },
         "attributesForFaceting": ["name"],
+        "sortableAttributes": [],
+        "queryRules": [],
+        "typoTolerance": {
+            "enabled": true,
+            "minWordSizeForTypos": {
+                "oneTypo": 5,
+                "twoTypos": 9
+            },
+            "disableOnWords": [],
+            "disableOnAttributes": []
+        },
     });
 
     server.update_all_settings(body.clone()).await;
@@ -81,6 +92,17 @@ async fn write_all_and_delete() {
         "stopWords": [],
         "synonyms": {},
         "attributesForFaceting": [],
+        "sortableAttributes": [],
+        "queryRules": [],
+        "typoTolerance": {
+            "enabled": true,
+            "minWordSizeForTypos": {
+                "oneTypo": 5,
+                "twoTypos": 9
+            },
+            "disableOnWords": [],
+            "disableOnAttributes": []
+        },
     });
 
     assert_json_eq!(expect, response, ordered: false);
@@ -132,6 +154,17 @@ async fn write_all_and_update() {
             "street": ["avenue"],
         },
         "attributesForFaceting": ["name"],
+        "sortableAttributes": [],
+        "queryRules": [],
+        "typoTolerance": {
+            "enabled": true,
+            "minWordSizeForTypos": {
+                "oneTypo": 5,
+                "twoTypos": 9
+            },
+            "disableOnWords": [],
+            "disableOnAttributes": []
+        },
     });
 
     server.update_all_settings(body.clone()).await;
@@ -173,6 +206,17 @@ async fn write_all_and_update() {
             "street": ["avenue"],
         },
         "attributesForFaceting": ["title"],
+        "sortableAttributes": [],
+        "queryRules": [],
+        "typoTolerance": {
+            "enabled": true,
+            "minWordSizeForTypos": {
+                "oneTypo": 5,
+                "twoTypos": 9
+            },
+            "disableOnWords": [],
+            "disableOnAttributes": []
+        },
     });
 
     server.update_all_settings(body).await;
@@ -210,6 +254,17 @@ async fn write_all_and_update() {
             "street": ["avenue"],
         },
         "attributesForFaceting": ["title"],
+        "sortableAttributes": [],
+        "queryRules": [],
+        "typoTolerance": {
+            "enabled": true,
+            "minWordSizeForTypos": {
+                "oneTypo": 5,
+                "twoTypos": 9
+            },
+            "disableOnWords": [],
+            "disableOnAttributes": []
+        },
     });
 
     assert_json_eq!(expected, response, ordered: false);
@@ -240,6 +295,17 @@ async fn test_default_settings() {
         "stopWords": [],
         "synonyms": {},
         "attributesForFaceting": [],
+        "sortableAttributes": [],
+        "queryRules": [],
+        "typoTolerance": {
+            "enabled": true,
+            "minWordSizeForTypos": {
+                "oneTypo": 5,
+                "twoTypos": 9
+            },
+            "disableOnWords": [],
+            "disableOnAttributes": []
+        },
     });
 
     let (response, _status_code) = server.get_all_settings().await;
@@ -273,6 +339,17 @@ async fn test_default_settings_2() {
         "stopWords": [],
         "synonyms": {},
         "attributesForFaceting": [],
+        "sortableAttributes": [],
+        "queryRules": [],
+        "typoTolerance": {
+            "enabled": true,
+            "minWordSizeForTypos": {
+                "oneTypo": 5,
+                "twoTypos": 9
+            },
+            "disableOnWords": [],
+            "disableOnAttributes": []
+        },
     });
 
     let (response, _status_code) = server.get_all_settings().await;
@@ -381,6 +458,17 @@ async fn write_setting_and_update_partial() {
             "street": ["avenue"],
         },
         "attributesForFaceting": [],
+        "sortableAttributes": [],
+        "queryRules": [],
+        "typoTolerance": {
+            "enabled": true,
+            "minWordSizeForTypos": {
+                "oneTypo": 5,
+                "twoTypos": 9
+            },
+            "disableOnWords": [],
+            "disableOnAttributes": []
+        },
     });
 
     let (response, _status_code) = server.get_all_settings().await;
@@ -513,6 +601,17 @@ async fn test_displayed_attributes_field() {
             "street": ["avenue"],
         },
         "attributesForFaceting": ["name"],
+        "sortableAttributes": [],
+        "queryRules": [],
+        "typoTolerance": {
+            "enabled": true,
+            "minWordSizeForTypos": {
+                "oneTypo": 5,
+                "twoTypos": 9
+            },
+            "disableOnWords": [],
+            "disableOnAttributes": []
+        },
     });
 
     server.update_all_settings(body.clone()).await;
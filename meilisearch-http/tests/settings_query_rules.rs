@@ -0,0 +1,138 @@
+use serde_json::json;
+
+mod common;
+
+async fn rules_server() -> common::Server {
+    let documents = json!([
+        { "id": 1, "title": "iphone 12" },
+        { "id": 2, "title": "iphone case" },
+        { "id": 3, "title": "samsung phone" },
+        { "id": 4, "title": "iphone charger" },
+    ]);
+
+    let mut server = common::Server::with_documents(documents).await;
+
+    let rules = json!([
+        {
+            "id": "promote-samsung",
+            "conditions": { "queryIs": "iphone" },
+            "consequences": {
+                "pin": [{ "id": "3", "position": 0 }],
+                "hide": ["2"],
+            },
+        },
+        {
+            "id": "galaxy",
+            "conditions": { "queryContains": "galaxy" },
+            "consequences": { "rewriteQuery": "samsung" },
+        },
+        {
+            "id": "accessories",
+            "conditions": { "queryIs": "accessories" },
+            "consequences": { "rewriteQuery": "iphone", "filters": "id = 4" },
+        },
+    ]);
+
+    server.update_query_rules(rules).await;
+    server
+}
+
+#[actix_rt::test]
+async fn write_and_delete_query_rules() {
+    let mut server = rules_server().await;
+
+    let (response, status_code) = server.get_query_rules().await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response.as_array().unwrap().len(), 3);
+    assert_eq!(response[0]["consequences"]["hide"], json!(["2"]));
+
+    server.delete_query_rules().await;
+
+    let (response, status_code) = server.get_query_rules().await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response, json!([]));
+}
+
+#[actix_rt::test]
+async fn query_rules_with_the_same_id() {
+    let mut server = rules_server().await;
+
+    let rules = json!([{ "id": "rule" }, { "id": "rule" }]);
+    let (response, status_code) = server.update_query_rules_sync(rules).await;
+    assert_eq!(status_code, 400);
+    assert_eq!(response["errorCode"], "bad_request");
+}
+
+#[actix_rt::test]
+async fn search_with_pinned_and_hidden_documents() {
+    let mut server = rules_server().await;
+
+    let query = json!({ "q": "iphone" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let ids = common::ids(&response);
+        assert_eq!(ids[0], 3);
+        let mut others = ids[1..].to_vec();
+        others.sort_unstable();
+        assert_eq!(others, vec![1, 4]);
+        assert_eq!(response["nbHits"], 3);
+    });
+
+    // the pinned document keeps its position across the pages
+    let query = json!({ "q": "iphone", "offset": 1, "limit": 2 });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let mut ids = common::ids(&response);
+        ids.sort_unstable();
+        assert_eq!(ids, vec![1, 4]);
+    });
+
+    // the rule only applies to this exact query
+    let query = json!({ "q": "iphone case" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::ids(&response)[0], 2);
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_pinned_documents_and_filters() {
+    let mut server = rules_server().await;
+
+    // the pinned document doesn't match the filters
+    let query = json!({ "q": "iphone", "filters": "id != 3" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        let mut ids = common::ids(&response);
+        ids.sort_unstable();
+        assert_eq!(ids, vec![1, 4]);
+        assert_eq!(response["nbHits"], 2);
+    });
+
+    // the pinned documents are placed by position, a cursor can't be used
+    let (response, _status_code) = server.search_post(json!({ "q": "iphon", "limit": 1 })).await;
+    let cursor = response["nextCursor"].as_str().unwrap();
+    let query = json!({ "q": "iphone", "cursor": cursor });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 400);
+        assert_eq!(response["errorCode"], "bad_parameter");
+    });
+}
+
+#[actix_rt::test]
+async fn search_with_rewritten_query() {
+    let mut server = rules_server().await;
+
+    let query = json!({ "q": "Galaxy" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::ids(&response), vec![3]);
+        assert_eq!(response["query"], "Galaxy");
+    });
+
+    let query = json!({ "q": "accessories" });
+    test_post_get_search!(server, query, |response, status_code| {
+        assert_eq!(status_code, 200);
+        assert_eq!(common::ids(&response), vec![4]);
+    });
+}
@@ -134,6 +134,21 @@ async fn send_malformed_custom_rule() {
     assert_eq!(status_code, 400);
 }
 
+#[actix_rt::test]
+async fn send_duplicated_rules() {
+    let mut server = common::Server::with_uid("test");
+    let body = json!({
+        "uid": "test",
+        "primaryKey": "id",
+    });
+    server.create_index(body).await;
+
+    let body = json!(["typo", "words", "typo"]);
+
+    let (_response, status_code) = server.update_ranking_rules_sync(body).await;
+    assert_eq!(status_code, 400);
+}
+
 // Test issue https://github.com/meilisearch/MeiliSearch/issues/521
 #[actix_rt::test]
 async fn write_custom_ranking_and_index_documents() {
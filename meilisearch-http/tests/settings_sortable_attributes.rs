@@ -0,0 +1,51 @@
+use assert_json_diff::assert_json_eq;
+use serde_json::json;
+
+mod common;
+
+#[actix_rt::test]
+async fn write_all_and_delete() {
+    let mut server = common::Server::test_server().await;
+
+    // 1 - Send the sortable attributes
+
+    let body = json!(["age", "registered"]);
+
+    server.update_sortable_attributes(body.clone()).await;
+
+    // 2 - Get the sortable attributes and compare to the previous ones
+
+    let (response, status_code) = server.get_sortable_attributes().await;
+    assert_eq!(status_code, 200);
+    assert_json_eq!(body, response, ordered: false);
+
+    // 3 - Delete the sortable attributes
+
+    server.delete_sortable_attributes().await;
+
+    // 4 - Check they are back to the default value
+
+    let (response, status_code) = server.get_sortable_attributes().await;
+    assert_eq!(status_code, 200);
+    assert_json_eq!(json!([]), response, ordered: false);
+}
+
+#[actix_rt::test]
+async fn sortable_attributes_do_not_change_ranking_rules() {
+    let mut server = common::Server::test_server().await;
+
+    server.update_sortable_attributes(json!(["age"])).await;
+
+    let (response, _status_code) = server.get_ranking_rules().await;
+
+    let expected = json!([
+        "typo",
+        "words",
+        "proximity",
+        "attribute",
+        "wordsPosition",
+        "exactness",
+    ]);
+
+    assert_json_eq!(expected, response, ordered: true);
+}
@@ -0,0 +1,93 @@
+use assert_json_diff::assert_json_eq;
+use serde_json::{json, Value};
+
+mod common;
+
+fn default_typo_tolerance() -> Value {
+    json!({
+        "enabled": true,
+        "minWordSizeForTypos": {
+            "oneTypo": 5,
+            "twoTypos": 9
+        },
+        "disableOnWords": [],
+        "disableOnAttributes": []
+    })
+}
+
+async fn nb_hits(server: &mut common::Server, q: &str) -> usize {
+    let (response, _status_code) = server.search_post(json!({ "q": q, "limit": 100 })).await;
+    response["hits"].as_array().unwrap().len()
+}
+
+#[actix_rt::test]
+async fn write_all_and_delete() {
+    let mut server = common::Server::test_server().await;
+
+    // 1 - Get the default typo tolerance
+
+    let (response, status_code) = server.get_typo_tolerance().await;
+    assert_eq!(status_code, 200);
+    assert_json_eq!(default_typo_tolerance(), response, ordered: false);
+
+    // 2 - Send a partial typo tolerance, missing fields take their default value
+
+    server.update_typo_tolerance(json!({ "disableOnWords": ["exercitation"] })).await;
+
+    let mut expected = default_typo_tolerance();
+    expected["disableOnWords"] = json!(["exercitation"]);
+
+    let (response, _status_code) = server.get_typo_tolerance().await;
+    assert_json_eq!(expected, response, ordered: false);
+
+    // 3 - Delete the typo tolerance and check it is back to default
+
+    server.delete_typo_tolerance().await;
+
+    let (response, _status_code) = server.get_typo_tolerance().await;
+    assert_json_eq!(default_typo_tolerance(), response, ordered: false);
+}
+
+#[actix_rt::test]
+async fn typo_tolerance_is_honored_at_search() {
+    let mut server = common::Server::test_server().await;
+
+    // one typo on "exercitation"
+    let query = "exercitatiom";
+    assert!(nb_hits(&mut server, query).await > 0);
+
+    server.update_typo_tolerance(json!({ "enabled": false })).await;
+    assert_eq!(nb_hits(&mut server, query).await, 0);
+
+    server.update_typo_tolerance(json!({ "minWordSizeForTypos": { "oneTypo": 13, "twoTypos": 15 } })).await;
+    assert_eq!(nb_hits(&mut server, query).await, 0);
+
+    server.update_typo_tolerance(json!({ "minWordSizeForTypos": { "oneTypo": 3, "twoTypos": 15 } })).await;
+    assert!(nb_hits(&mut server, query).await > 0);
+
+    server.update_typo_tolerance(json!({ "disableOnWords": ["Exercitatiom"] })).await;
+    assert_eq!(nb_hits(&mut server, query).await, 0);
+
+    // the words are normalized like the query words
+    server.update_typo_tolerance(json!({})).await;
+    assert!(nb_hits(&mut server, query).await > 0);
+    server.update_typo_tolerance(json!({ "disableOnWords": ["ExercitàtiOm"] })).await;
+    assert_eq!(nb_hits(&mut server, query).await, 0);
+
+    server.update_typo_tolerance(json!({ "disableOnAttributes": ["about"] })).await;
+    assert_eq!(nb_hits(&mut server, query).await, 0);
+    assert!(nb_hits(&mut server, "exercitation").await > 0);
+}
+
+#[actix_rt::test]
+async fn invalid_typo_tolerance() {
+    let mut server = common::Server::test_server().await;
+
+    let body = json!({ "minWordSizeForTypos": { "oneTypo": 10, "twoTypos": 5 } });
+    let (_response, status_code) = server.update_typo_tolerance_sync(body).await;
+    assert_eq!(status_code, 400);
+
+    let body = json!({ "typo": true });
+    let (_response, status_code) = server.update_typo_tolerance_sync(body).await;
+    assert_eq!(status_code, 400);
+}
@@ -0,0 +1,40 @@
+use serde_json::json;
+
+mod common;
+
+#[actix_rt::test]
+async fn similar_documents_exclude_source() {
+    let mut server = common::Server::test_server().await;
+
+    let (response, status_code) = server.get_request("/indexes/test/documents/0/similar").await;
+    assert_eq!(status_code, 200);
+    let hits = response["hits"].as_array().unwrap();
+    assert!(!hits.is_empty());
+    assert!(hits.iter().all(|hit| hit["id"] != json!(0)));
+
+    let (response, status_code) = server.get_request("/indexes/test/documents/0/similar?limit=3&attributesToRetrieve=id").await;
+    assert_eq!(status_code, 200);
+    let hits = response["hits"].as_array().unwrap();
+    assert_eq!(hits.len(), 3);
+    assert!(hits.iter().all(|hit| hit.as_object().unwrap().keys().eq(["id"].iter())));
+}
+
+#[actix_rt::test]
+async fn similar_documents_with_filters() {
+    let mut server = common::Server::test_server().await;
+
+    let (response, status_code) = server.get_request("/indexes/test/documents/0/similar?filters=color%3D%27blue%27&attributesToRetrieve=color").await;
+    assert_eq!(status_code, 200);
+    let hits = response["hits"].as_array().unwrap();
+    assert!(!hits.is_empty());
+    assert!(hits.iter().all(|hit| hit["color"] == json!("blue")));
+}
+
+#[actix_rt::test]
+async fn similar_documents_of_unknown_document() {
+    let mut server = common::Server::test_server().await;
+
+    let (response, status_code) = server.get_request("/indexes/test/documents/1000/similar").await;
+    assert_eq!(status_code, 404);
+    assert_eq!(response["errorCode"], "document_not_found");
+}
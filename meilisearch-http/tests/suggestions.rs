@@ -0,0 +1,59 @@
+use serde_json::json;
+
+mod common;
+
+#[actix_rt::test]
+async fn suggestions_complete_prefix() {
+    let mut server = common::Server::test_server().await;
+
+    let (response, status_code) = server.get_request("/indexes/test/suggestions?q=exer").await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response, json!([{ "word": "exercitation", "count": 43 }]));
+
+    let (response, status_code) = server.get_request("/indexes/test/suggestions?q=e&limit=5").await;
+    assert_eq!(status_code, 200);
+    let suggestions = response.as_array().unwrap();
+    assert_eq!(suggestions.len(), 5);
+    let counts: Vec<_> = suggestions.iter().map(|s| s["count"].as_u64().unwrap()).collect();
+    assert!(counts.windows(2).all(|w| w[0] >= w[1]));
+    assert!(suggestions.iter().all(|s| s["word"].as_str().unwrap().starts_with('e')));
+}
+
+#[actix_rt::test]
+async fn suggestions_limit_is_capped() {
+    let mut server = common::Server::test_server().await;
+
+    let (response, status_code) = server.get_request("/indexes/test/suggestions?q=e&limit=100").await;
+    assert_eq!(status_code, 200);
+    assert!(!response.as_array().unwrap().is_empty());
+
+    let (response, status_code) = server.get_request("/indexes/test/suggestions?q=e&limit=101").await;
+    assert_eq!(status_code, 400);
+    assert_eq!(response["errorCode"], "bad_parameter");
+}
+
+#[actix_rt::test]
+async fn suggestions_restricted_to_attributes() {
+    let mut server = common::Server::test_server().await;
+
+    let (response, status_code) = server.get_request("/indexes/test/suggestions?q=exer&attributes=about").await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response, json!([{ "word": "exercitation", "count": 43 }]));
+
+    let (response, status_code) = server.get_request("/indexes/test/suggestions?q=exer&attributes=name").await;
+    assert_eq!(status_code, 200);
+    assert_eq!(response, json!([]));
+
+    let (response, status_code) = server.get_request("/indexes/test/suggestions?q=exer&attributes=unknown").await;
+    assert_eq!(status_code, 400);
+    assert_eq!(response["errorCode"], "bad_parameter");
+}
+
+#[actix_rt::test]
+async fn suggestions_on_unknown_index() {
+    let mut server = common::Server::with_uid("test");
+
+    let (response, status_code) = server.get_request("/indexes/test/suggestions?q=exer").await;
+    assert_eq!(status_code, 404);
+    assert_eq!(response["errorCode"], "index_not_found");
+}